@@ -0,0 +1,187 @@
+//! Regenerates `src/targets.rs` from the targets the installed rustc knows
+//! and the rustc book's platform support page:
+//!
+//! ```text
+//! cargo run --example gen_targets > src/targets.rs
+//! cargo run --example gen_targets -- path/to/platform-support.md > src/targets.rs
+//! ```
+//!
+//! Without an argument the page is read from the toolchain's own docs,
+//! `<sysroot>/share/doc/rust/html/rustc/platform-support.html`, so the
+//! toolchain needs the `rust-docs` component. Both the markdown source and
+//! the rendered HTML are understood.
+
+use std::{collections::BTreeMap, env, fs, path::PathBuf, process::Command};
+
+struct Support {
+    tier: u8,
+    host_tools: bool,
+}
+
+fn rustc(args: &[&str]) -> String {
+    let output = Command::new("rustc")
+        .args(args)
+        .output()
+        .expect("failed to run rustc");
+    assert!(output.status.success(), "rustc {:?} failed", args);
+    String::from_utf8(output.stdout).expect("rustc printed invalid UTF-8")
+}
+
+/// `line` with HTML tags removed.
+fn strip_tags(line: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// The tier and host tools a section heading stands for, `None` for host
+/// tools when each row says.
+fn section(heading: &str) -> Option<(u8, Option<bool>)> {
+    let heading = heading.to_lowercase();
+    let tier = if heading.contains("tier 1") {
+        1
+    } else if heading.contains("tier 2") {
+        2
+    } else if heading.contains("tier 3") {
+        3
+    } else {
+        return None;
+    };
+
+    let host_tools = if heading.contains("without host tools") {
+        Some(false)
+    } else if heading.contains("with host tools") {
+        Some(true)
+    } else if tier == 3 {
+        None
+    } else {
+        Some(false)
+    };
+    Some((tier, host_tools))
+}
+
+/// The cells of a markdown or HTML table row.
+fn cells(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    if let Some(row) = line.strip_prefix("<tr><td") {
+        return Some(row.split("</td>").map(strip_tags).collect());
+    }
+    if line.starts_with('|') {
+        return Some(
+            line.trim_matches('|')
+                .split('|')
+                .map(str::to_string)
+                .collect(),
+        );
+    }
+    None
+}
+
+/// The target named in a table cell like `` [`x86_64-unknown-linux-gnu`](...) ``.
+fn cell_target(line: &str, cell: &str) -> Option<String> {
+    let code = if line.trim().starts_with('<') {
+        let start = line.find("<code>")? + "<code>".len();
+        let end = start + line[start..].find("</code>")?;
+        &line[start..end]
+    } else {
+        let start = cell.find('`')? + 1;
+        let end = start + cell[start..].find('`')?;
+        &cell[start..end]
+    };
+    Some(code.trim().to_string())
+}
+
+fn parse_support(page: &str) -> BTreeMap<String, Support> {
+    let mut support = BTreeMap::new();
+    let mut current = None;
+
+    for line in page.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("## ") || trimmed.starts_with("<h2") {
+            current = section(&strip_tags(trimmed));
+            continue;
+        }
+        let Some((tier, host_tools)) = current else {
+            continue;
+        };
+        let Some(cells) = cells(line) else {
+            continue;
+        };
+        let Some(target) = cells.first().and_then(|cell| cell_target(line, cell)) else {
+            continue;
+        };
+
+        // Tier 3 tables have `target | std | host | notes` columns.
+        let host_tools =
+            host_tools.unwrap_or_else(|| cells.get(2).is_some_and(|c| c.contains('✓')));
+        support
+            .entry(target)
+            .or_insert(Support { tier, host_tools });
+    }
+    support
+}
+
+fn main() {
+    let page_path = match env::args_os().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(rustc(&["--print", "sysroot"]).trim())
+            .join("share/doc/rust/html/rustc/platform-support.html"),
+    };
+    let page = fs::read_to_string(&page_path)
+        .unwrap_or_else(|e| panic!("reading {}: {}", page_path.display(), e));
+    let support = parse_support(&page);
+
+    let version = rustc(&["--version"]);
+    let mut targets: Vec<String> = rustc(&["--print", "target-list"])
+        .lines()
+        .map(str::to_string)
+        .collect();
+    targets.sort();
+
+    println!("//! Every target rustc knows about, with its support tier.");
+    println!("//!");
+    println!("//! Generated by `cargo run --example gen_targets` from");
+    println!("//! {}, do not edit.", version.trim());
+    println!();
+    println!("/// A target built into rustc.");
+    println!("#[derive(Debug, Clone, Copy, PartialEq, Eq)]");
+    println!("pub struct Target {{");
+    println!("    pub triple: &'static str,");
+    println!("    /// Support tier, 1 to 3. Targets the platform support page doesn't");
+    println!("    /// list are counted as tier 3.");
+    println!("    pub tier: u8,");
+    println!("    /// Whether rustc and cargo are published for it.");
+    println!("    pub host_tools: bool,");
+    println!("}}");
+    println!();
+    println!("/// Sorted by triple.");
+    println!("pub static TARGETS: &[Target] = &[");
+    for triple in &targets {
+        let (tier, host_tools) = match support.get(triple) {
+            Some(s) => (s.tier, s.host_tools),
+            None => (3, false),
+        };
+        println!("    Target {{");
+        println!("        triple: {:?},", triple);
+        println!("        tier: {},", tier);
+        println!("        host_tools: {},", host_tools);
+        println!("    }},");
+    }
+    println!("];");
+    println!();
+    println!("/// The target named `triple`, if rustc has it.");
+    println!("pub fn find(triple: &str) -> Option<&'static Target> {{");
+    println!("    TARGETS");
+    println!("        .binary_search_by(|t| t.triple.cmp(triple))");
+    println!("        .ok()");
+    println!("        .map(|i| &TARGETS[i])");
+    println!("}}");
+}
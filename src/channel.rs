@@ -0,0 +1,373 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::dist::DistServers;
+use crate::error::{Error, Result};
+use crate::signature::{self, Keyring};
+
+/// What the user asked to install, before it has been resolved to a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolchainSpec {
+    /// `stable`, `beta` or `nightly`, optionally pinned to a release date.
+    Channel { name: String, date: Option<String> },
+    /// An exact release such as `1.76.0`.
+    Version(Version),
+    /// The latest patch release of `major.minor`, e.g. `1.76`.
+    Minor(u64, u64),
+    /// The newest stable release matching a requirement such as `>=1.70`.
+    Requirement(VersionReq),
+}
+
+static CHANNELS: &[&str] = &["stable", "beta", "nightly"];
+
+fn is_date(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    parts.len() == 3
+        && [4, 2, 2]
+            .iter()
+            .zip(&parts)
+            .all(|(len, part)| part.len() == *len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl FromStr for ToolchainSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        for channel in CHANNELS {
+            if s == *channel {
+                return Ok(ToolchainSpec::Channel {
+                    name: channel.to_string(),
+                    date: None,
+                });
+            }
+            if let Some(date) = s
+                .strip_prefix(channel)
+                .and_then(|rest| rest.strip_prefix('-'))
+            {
+                if is_date(date) {
+                    return Ok(ToolchainSpec::Channel {
+                        name: channel.to_string(),
+                        date: Some(date.to_string()),
+                    });
+                }
+            }
+        }
+
+        if let Ok(version) = Version::parse(s) {
+            return Ok(ToolchainSpec::Version(version));
+        }
+
+        let numbers: Vec<&str> = s.split('.').collect();
+        if numbers.len() == 2 {
+            if let (Ok(major), Ok(minor)) = (numbers[0].parse(), numbers[1].parse()) {
+                return Ok(ToolchainSpec::Minor(major, minor));
+            }
+        }
+
+        VersionReq::parse(s)
+            .map(ToolchainSpec::Requirement)
+            .map_err(|_| Error::Toolchain(format!("`{}` is not a channel or version", s)))
+    }
+}
+
+impl fmt::Display for ToolchainSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolchainSpec::Channel { name, date: None } => write!(f, "{}", name),
+            ToolchainSpec::Channel {
+                name,
+                date: Some(date),
+            } => write!(f, "{}-{}", name, date),
+            ToolchainSpec::Version(version) => write!(f, "{}", version),
+            ToolchainSpec::Minor(major, minor) => write!(f, "{}.{}", major, minor),
+            ToolchainSpec::Requirement(req) => write!(f, "{}", req),
+        }
+    }
+}
+
+/// A `channel-rust-*.toml` manifest from the dist server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "manifest-version")]
+    pub manifest_version: String,
+    pub date: String,
+    #[serde(default)]
+    pub pkg: HashMap<String, Package>,
+    #[serde(default)]
+    pub renames: HashMap<String, Rename>,
+    #[serde(default)]
+    pub profiles: HashMap<String, Vec<String>>,
+    /// The manifest as it was downloaded.
+    #[serde(skip)]
+    contents: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Package {
+    pub version: String,
+    #[serde(default)]
+    pub target: HashMap<String, PackageTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageTarget {
+    pub available: bool,
+    pub url: Option<String>,
+    pub hash: Option<String>,
+    pub xz_url: Option<String>,
+    pub xz_hash: Option<String>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    #[serde(default)]
+    pub extensions: Vec<Component>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Component {
+    pub pkg: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rename {
+    pub to: String,
+}
+
+impl Manifest {
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut manifest: Manifest =
+            toml::from_str(contents).map_err(|e| Error::Manifest(e.to_string()))?;
+        manifest.contents = contents.to_string();
+        Ok(manifest)
+    }
+
+    /// Where an installed toolchain keeps the manifest it was installed from.
+    pub fn installed_path(prefix: &Path) -> PathBuf {
+        prefix.join("lib/rustlib/multirust-channel-manifest.toml")
+    }
+
+    /// The manifest a toolchain in `prefix` was installed from.
+    pub fn load_installed(prefix: &Path) -> Result<Self> {
+        let path = Manifest::installed_path(prefix);
+        let contents = fs::read_to_string(&path).map_err(|_| {
+            Error::Toolchain(format!("no toolchain installed in {}", prefix.display()))
+        })?;
+        Manifest::parse(&contents)
+    }
+
+    /// Record this manifest as the one the toolchain in `prefix` came from.
+    pub fn save_installed(&self, prefix: &Path) -> Result<()> {
+        let path = Manifest::installed_path(prefix);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &self.contents)?;
+        Ok(())
+    }
+
+    /// The release version of the `rust` package, e.g. `1.76.0` or `1.78.0-nightly`.
+    pub fn rust_version(&self) -> Option<Version> {
+        let version = &self.pkg.get("rust")?.version;
+        Version::parse(version.split_whitespace().next()?).ok()
+    }
+
+    /// The current name of a package that may have been renamed, e.g.
+    /// `clippy` is published as `clippy-preview`.
+    pub fn rename(&self, name: &str) -> String {
+        match self.renames.get(name) {
+            Some(rename) => rename.to.clone(),
+            None => name.to_string(),
+        }
+    }
+
+    /// Targets the release has a standard library for, sorted.
+    pub fn targets(&self) -> Vec<String> {
+        let mut targets: Vec<String> = match self.pkg.get("rust-std") {
+            Some(std) => std
+                .target
+                .iter()
+                .filter(|(_, t)| t.available)
+                .map(|(name, _)| name.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+        targets.sort();
+        targets
+    }
+
+    /// The artifact of `pkg` built for `target`, if the release has one.
+    pub fn get(&self, pkg: &str, target: &str) -> Option<&PackageTarget> {
+        let package = self.pkg.get(pkg)?;
+        package
+            .target
+            .get(target)
+            .or_else(|| package.target.get("*"))
+            .filter(|t| t.available)
+    }
+}
+
+fn manifest_path(name: &str, date: Option<&str>) -> String {
+    match date {
+        Some(date) => format!("dist/{}/channel-rust-{}.toml", date, name),
+        None => format!("dist/channel-rust-{}.toml", name),
+    }
+}
+
+/// Where the dist server and mirrors fetch from, and whose signatures to accept.
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    pub dist: &'a DistServers,
+    pub keyring: &'a Keyring,
+}
+
+/// Download and verify a manifest, returning `None` if no server has it.
+pub async fn fetch_manifest(
+    name: &str,
+    date: Option<&str>,
+    source: Source<'_>,
+) -> Result<Option<Manifest>> {
+    let path = manifest_path(name, date);
+    let urls = source.dist.urls(&path);
+    let Some(contents) = source.dist.fetch_text(&urls).await? else {
+        return Ok(None);
+    };
+
+    let asc = signature::fetch_signature(source.dist, &urls).await?;
+    source.keyring.verify(&path, contents.as_bytes(), &asc)?;
+
+    Manifest::parse(&contents).map(Some)
+}
+
+async fn require_manifest(name: &str, date: Option<&str>, source: Source<'_>) -> Result<Manifest> {
+    fetch_manifest(name, date, source).await?.ok_or_else(|| {
+        if source.dist.offline() {
+            return Error::Offline(format!(
+                "{} is not available locally",
+                manifest_path(name, date)
+            ));
+        }
+        Error::Toolchain(match date {
+            Some(date) => format!("no {} release on {}", name, date),
+            None => format!("no release found for `{}`", name),
+        })
+    })
+}
+
+/// Find the newest stable release matching `req`, newest minor first.
+async fn resolve_requirement(req: &VersionReq, source: Source<'_>) -> Result<Manifest> {
+    let stable = require_manifest("stable", None, source).await?;
+    let latest = stable
+        .rust_version()
+        .ok_or_else(|| Error::Manifest("stable manifest has no rust version".to_string()))?;
+
+    if req.matches(&latest) {
+        return Ok(stable);
+    }
+
+    for minor in (0..=latest.minor).rev() {
+        // Patch releases never go far, so skip minors that can't possibly match
+        // instead of downloading their manifests.
+        if !(0..10).any(|patch| req.matches(&Version::new(latest.major, minor, patch))) {
+            continue;
+        }
+
+        let name = format!("{}.{}", latest.major, minor);
+        let Some(manifest) = fetch_manifest(&name, None, source).await? else {
+            continue;
+        };
+        let Some(newest) = manifest.rust_version() else {
+            continue;
+        };
+        if req.matches(&newest) {
+            return Ok(manifest);
+        }
+
+        for patch in (0..newest.patch).rev() {
+            if req.matches(&Version::new(newest.major, newest.minor, patch)) {
+                let name = format!("{}.{}.{}", newest.major, newest.minor, patch);
+                if let Some(manifest) = fetch_manifest(&name, None, source).await? {
+                    return Ok(manifest);
+                }
+            }
+        }
+    }
+
+    Err(Error::Toolchain(format!(
+        "no stable release matches `{}`",
+        req
+    )))
+}
+
+/// Resolve a toolchain spec to the manifest of the release it names.
+pub async fn resolve(spec: &ToolchainSpec, source: Source<'_>) -> Result<Manifest> {
+    match spec {
+        ToolchainSpec::Channel { name, date } => {
+            require_manifest(name, date.as_deref(), source).await
+        }
+        ToolchainSpec::Version(version) => {
+            require_manifest(&version.to_string(), None, source).await
+        }
+        ToolchainSpec::Minor(major, minor) => {
+            require_manifest(&format!("{}.{}", major, minor), None, source).await
+        }
+        ToolchainSpec::Requirement(req) => resolve_requirement(req, source).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> ToolchainSpec {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn channels() {
+        assert_eq!(
+            parse("stable"),
+            ToolchainSpec::Channel {
+                name: "stable".to_string(),
+                date: None
+            }
+        );
+        assert_eq!(
+            parse("nightly-2024-02-01"),
+            ToolchainSpec::Channel {
+                name: "nightly".to_string(),
+                date: Some("2024-02-01".to_string())
+            }
+        );
+        assert!("nightly-2024-2-1".parse::<ToolchainSpec>().is_err());
+    }
+
+    #[test]
+    fn versions() {
+        assert_eq!(
+            parse("1.76.0"),
+            ToolchainSpec::Version(Version::new(1, 76, 0))
+        );
+        assert_eq!(parse(" 1.76 "), ToolchainSpec::Minor(1, 76));
+        assert_eq!(
+            parse(">=1.70"),
+            ToolchainSpec::Requirement(VersionReq::parse(">=1.70").unwrap())
+        );
+        assert!("stabel".parse::<ToolchainSpec>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in ["stable", "beta-2024-03-01", "1.76.0", "1.76", ">=1.70"] {
+            assert_eq!(parse(s).to_string(), s);
+        }
+    }
+}
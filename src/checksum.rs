@@ -0,0 +1,45 @@
+use std::{fs::File, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::dist::DistServers;
+use crate::error::{Error, Result};
+
+/// Hash a file without reading it into memory.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fetch the `.sha256` file published next to a file, trying each of its
+/// `urls` in turn.
+///
+/// The file holds `<hash>  <file name>`, only the hash is returned.
+pub async fn fetch_sha256(dist: &DistServers, urls: &[String]) -> Result<String> {
+    let name = urls.first().map(String::as_str).unwrap_or_default();
+    let sha: Vec<String> = urls.iter().map(|url| format!("{}.sha256", url)).collect();
+    let contents = dist
+        .fetch_text(&sha)
+        .await?
+        .ok_or_else(|| Error::Toolchain(format!("no checksum published for {}", name)))?;
+
+    contents
+        .split_whitespace()
+        .next()
+        .map(|hash| hash.to_ascii_lowercase())
+        .ok_or_else(|| Error::Manifest(format!("empty checksum file for {}", name)))
+}
+
+/// Compare an already computed hex SHA-256 digest with the expected one.
+pub fn verify_digest(name: &str, actual: &str, expected: &str) -> Result<()> {
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(Error::Checksum {
+            file: name.to_string(),
+            expected: expected.trim().to_ascii_lowercase(),
+            actual: actual.to_ascii_lowercase(),
+        })
+    }
+}
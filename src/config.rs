@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::dist::{DistServers, DEFAULT_DIST_SERVER};
+use crate::error::{Error, Result};
+use crate::home;
+
+/// Settings read from `$GET_RUST_HOME/config.toml`.
+///
+/// ```toml
+/// dist_server = "https://artifacts.example.com/rust"
+/// mirrors = ["https://static.rust-lang.org"]
+/// trusted_keys = ["/etc/get-rust/mirror-key.asc"]
+/// offline = false
+/// update_root = "https://artifacts.example.com/get-rust"
+///
+/// [aliases]
+/// board = "thumbv7em-none-eabihf"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub dist_server: Option<String>,
+    pub mirrors: Vec<String>,
+    pub trusted_keys: Vec<PathBuf>,
+    pub offline: bool,
+    /// Where `self update` looks for new releases.
+    pub update_root: Option<String>,
+    /// Extra names for targets, on top of the built-in ones like `linux-x64`.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        home::get_rust_home().join("config.toml")
+    }
+
+    /// Load the config file, or the defaults if there isn't one.
+    pub fn load() -> Result<Self> {
+        let path = Config::path();
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| Error::Config(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The dist server and mirrors to use.
+    ///
+    /// The server comes from, in order: `flag`, `GET_RUST_DIST_SERVER`,
+    /// `RUSTUP_DIST_SERVER`, the config file and finally the official server.
+    /// Mirrors given on the command line are tried before configured ones.
+    pub fn dist_servers(&self, flag: Option<String>, mirrors: Vec<String>) -> DistServers {
+        let primary = flag
+            .or_else(|| env_var("GET_RUST_DIST_SERVER"))
+            .or_else(|| env_var("RUSTUP_DIST_SERVER"))
+            .or_else(|| self.dist_server.clone())
+            .unwrap_or_else(|| DEFAULT_DIST_SERVER.to_string());
+
+        DistServers::new(
+            primary,
+            mirrors.into_iter().chain(self.mirrors.clone()).collect(),
+        )
+    }
+
+    /// Where to fetch get-rust releases from: `GET_RUST_UPDATE_ROOT`, then
+    /// the config file.
+    pub fn update_root(&self) -> Result<String> {
+        env_var("GET_RUST_UPDATE_ROOT")
+            .or_else(|| self.update_root.clone())
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "no update_root set in {} or GET_RUST_UPDATE_ROOT",
+                    Config::path().display()
+                ))
+            })
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
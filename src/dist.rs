@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+
+use reqwest::Url;
+
+use crate::error::{Error, Result};
+
+/// Root of the official Rust distribution server.
+pub const DEFAULT_DIST_SERVER: &str = "https://static.rust-lang.org";
+
+/// The dist server to download from, followed by mirrors to fail over to.
+///
+/// Every server is a root in the same layout as `static.rust-lang.org`,
+/// i.e. the same thing `RUSTUP_DIST_SERVER` points at. A `file://` root
+/// serves a dist tree from disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistServers {
+    servers: Vec<String>,
+    offline: bool,
+}
+
+impl Default for DistServers {
+    fn default() -> Self {
+        DistServers::new(DEFAULT_DIST_SERVER.to_string(), Vec::new())
+    }
+}
+
+impl DistServers {
+    pub fn new(primary: String, mirrors: Vec<String>) -> Self {
+        let mut servers: Vec<String> = Vec::new();
+        for server in std::iter::once(primary).chain(mirrors) {
+            let server = server.trim_end_matches('/').to_string();
+            if !server.is_empty() && !servers.contains(&server) {
+                servers.push(server);
+            }
+        }
+        if servers.is_empty() {
+            servers.push(DEFAULT_DIST_SERVER.to_string());
+        }
+        DistServers {
+            servers,
+            offline: false,
+        }
+    }
+
+    /// In offline mode only `file://` URLs are ever read.
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Fail if `url` would need the network while offline.
+    pub fn check_access(&self, url: &str) -> Result<()> {
+        if self.offline && local_path(url).is_none() {
+            Err(Error::Offline(format!(
+                "refusing to fetch {}, use a file:// dist server",
+                url
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn primary(&self) -> &str {
+        &self.servers[0]
+    }
+
+    pub fn servers(&self) -> &[String] {
+        &self.servers
+    }
+
+    /// `path` (relative to the server root, e.g. `dist/channel-rust-stable.toml`)
+    /// on every server, in the order they should be tried.
+    pub fn urls(&self, path: &str) -> Vec<String> {
+        let path = path.trim_start_matches('/');
+        self.servers
+            .iter()
+            .map(|server| format!("{}/{}", server, path))
+            .collect()
+    }
+
+    /// Rewrite a URL taken from a channel manifest, which always points at the
+    /// official server, to every configured server.
+    pub fn mirror_urls(&self, url: &str) -> Vec<String> {
+        match url.strip_prefix(DEFAULT_DIST_SERVER) {
+            Some(path) => self.urls(path),
+            None => vec![url.to_string()],
+        }
+    }
+
+    /// Read the first of `urls` that exists, trying the next one when a
+    /// server is unreachable or doesn't have the file. Returns `None` if none
+    /// of them do.
+    pub async fn fetch_text(&self, urls: &[String]) -> Result<Option<String>> {
+        let mut last_error = None;
+
+        for url in urls {
+            if let Err(e) = self.check_access(url) {
+                last_error = Some(e);
+                continue;
+            }
+
+            if let Some(path) = local_path(url) {
+                match tokio::fs::read_to_string(&path).await {
+                    Ok(contents) => return Ok(Some(contents)),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                    Err(e) => {
+                        last_error = Some(e.into());
+                        continue;
+                    }
+                }
+            }
+
+            let response = match reqwest::get(url).await {
+                Ok(response) => response,
+                Err(e) => {
+                    last_error = Some(e.into());
+                    continue;
+                }
+            };
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                continue;
+            }
+            match response.error_for_status() {
+                Ok(response) => return Ok(Some(response.text().await?)),
+                Err(e) => last_error = Some(e.into()),
+            }
+        }
+
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The path a `file://` URL points at.
+pub fn local_path(url: &str) -> Option<PathBuf> {
+    if !url.starts_with("file:") {
+        return None;
+    }
+    Url::parse(url).ok()?.to_file_path().ok()
+}
+
+/// A `file://` URL for an absolute path.
+pub fn file_url(path: &Path) -> String {
+    Url::from_file_path(path)
+        .map(String::from)
+        .unwrap_or_else(|_| format!("file://{}", path.display()))
+}
@@ -0,0 +1,392 @@
+use std::path::{Path, PathBuf};
+
+use indicatif::ProgressBar;
+use reqwest::{
+    header::{HeaderMap, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    Response, StatusCode,
+};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+
+use crate::dist::{self, DistServers};
+use crate::error::{Error, Result};
+use crate::progress;
+
+/// How many times a dropped download is resumed before giving up.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Stream a file into `dest` from the first of `urls` that works, returning
+/// the hex SHA-256 of what was written.
+///
+/// Each URL is retried and resumed as described for [`download_from`] before
+/// moving on to the next mirror. `file://` URLs are copied from disk, and are
+/// the only ones allowed when `dist` is offline.
+pub async fn download_file(
+    dist: &DistServers,
+    urls: &[String],
+    dest: &Path,
+    pb: &ProgressBar,
+) -> Result<String> {
+    let mut last_error = None;
+
+    for url in urls {
+        let result = match dist.check_access(url) {
+            Ok(()) => match dist::local_path(url) {
+                Some(path) => copy_local(&path, dest, pb).await,
+                None => download_from(url, dest, pb).await,
+            },
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(digest) => return Ok(digest),
+            Err(e) => {
+                if urls.len() > 1 {
+                    pb.println(format!("Download from {} failed: {}", url, e));
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::Install("no download URL".to_string())))
+}
+
+/// Stream `url` into `dest`, returning the hex SHA-256 of what was written.
+///
+/// The body is written to `dest` with a `.partial` suffix and only renamed
+/// into place once it has been received in full, so memory use stays bounded
+/// by the size of a single chunk. Progress is reported on `pb`, see
+/// [`progress::download_bar`].
+///
+/// A `.partial` file left behind by an earlier run, or by a dropped
+/// connection, is resumed with a `Range` request. The URL it came from and
+/// the server's `ETag` or `Last-Modified` are kept next to it, the latter
+/// sent as `If-Range`, so a file that changed on the server, or a partial of
+/// another URL with the same file name, is downloaded again from scratch.
+pub async fn download_from(url: &str, dest: &Path, pb: &ProgressBar) -> Result<String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let partial = with_suffix(dest, ".partial");
+    let source = with_suffix(dest, ".partial.source");
+
+    let mut attempt = 1;
+    loop {
+        match try_download(url, &partial, &source, pb).await {
+            Ok(digest) => {
+                let _ = fs::remove_file(&source).await;
+                fs::rename(&partial, dest).await?;
+                return Ok(digest);
+            }
+            Err(e) if attempt < MAX_ATTEMPTS && is_transient(&e) => {
+                pb.println(format!("Download interrupted ({}), resuming...", e));
+                tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Copy a file out of a local dist tree, hashing it on the way.
+async fn copy_local(src: &Path, dest: &Path, pb: &ProgressBar) -> Result<String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut source = match fs::File::open(src).await {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(Error::Offline(format!(
+                "{} is not available locally",
+                src.display()
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    progress::set_download_length(pb, source.metadata().await?.len());
+
+    let partial = with_suffix(dest, ".partial");
+    let mut file = fs::File::create(&partial).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = source.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        file.write_all(&buf[..n]).await?;
+        pb.inc(n as u64);
+    }
+
+    file.flush().await?;
+    drop(file);
+    fs::rename(&partial, dest).await?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// What to do with the body of a download response, see [`resume_action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    /// Append the body to the partial file.
+    Append,
+    /// Write the body to the partial file from scratch.
+    Restart,
+    /// Ask for the whole file again, the body can't be used.
+    Refetch,
+    /// The server sent part of the file when asked for all of it.
+    Error,
+}
+
+/// Decide what to do with a response with `status` and `content_range` to
+/// a request for `url`, given the `.partial.source` file's contents. The
+/// request asked for the rest of the partial file from `offset` on if it's
+/// from `url`, and for the whole file otherwise.
+///
+/// The source file holds the URL the partial file is from, then the
+/// validator to resume it with, if the server sent one.
+fn resume_action(
+    source: &str,
+    url: &str,
+    offset: u64,
+    status: StatusCode,
+    content_range: Option<&str>,
+) -> Resume {
+    let resuming = offset > 0 && source.lines().next() == Some(url);
+    match status {
+        StatusCode::PARTIAL_CONTENT if !resuming => Resume::Error,
+        StatusCode::PARTIAL_CONTENT if content_range.and_then(range_start) == Some(offset) => {
+            Resume::Append
+        }
+        // Some other part of the file than the one asked for, which can't
+        // be appended or used on its own.
+        StatusCode::PARTIAL_CONTENT => Resume::Refetch,
+        // The partial file is at least as long as the real one, so it can't
+        // be a prefix of it.
+        StatusCode::RANGE_NOT_SATISFIABLE if resuming => Resume::Refetch,
+        _ => Resume::Restart,
+    }
+}
+
+async fn try_download(
+    url: &str,
+    partial: &Path,
+    source_path: &Path,
+    pb: &ProgressBar,
+) -> Result<String> {
+    let source = fs::read_to_string(source_path).await.unwrap_or_default();
+    let mut lines = source.lines();
+    let (mut hasher, mut offset) = match lines.next() {
+        Some(from) if from == url => hash_partial(partial).await?,
+        _ => (Sha256::new(), 0),
+    };
+    let validator = lines.next().filter(|v| !v.is_empty());
+
+    let mut response = send(url, offset, validator).await?;
+    let mut action = resume_action(
+        &source,
+        url,
+        offset,
+        response.status(),
+        content_range(&response),
+    );
+    if action == Resume::Refetch {
+        offset = 0;
+        response = send(url, 0, None).await?;
+        action = resume_action(
+            &source,
+            url,
+            offset,
+            response.status(),
+            content_range(&response),
+        );
+    }
+
+    let mut file = match action {
+        Resume::Append => fs::OpenOptions::new().append(true).open(partial).await?,
+        Resume::Restart => {
+            response.error_for_status_ref()?;
+
+            hasher = Sha256::new();
+            offset = 0;
+            let validator = response_validator(response.headers()).unwrap_or_default();
+            fs::write(source_path, format!("{}\n{}\n", url, validator)).await?;
+            fs::File::create(partial).await?
+        }
+        Resume::Refetch | Resume::Error => {
+            return Err(Error::Install(format!(
+                "{} sent part of the file when asked for all of it",
+                url
+            )));
+        }
+    };
+
+    if let Some(len) = response.content_length() {
+        progress::set_download_length(pb, offset + len);
+    }
+    pb.set_position(offset);
+
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        pb.inc(chunk.len() as u64);
+    }
+
+    file.flush().await?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+async fn send(url: &str, offset: u64, validator: Option<&str>) -> Result<Response> {
+    let mut request = reqwest::Client::new().get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+        if let Some(validator) = validator {
+            request = request.header(IF_RANGE, validator.trim());
+        }
+    }
+    Ok(request.send().await?)
+}
+
+/// Hash what has already been downloaded, returning the hasher and length.
+async fn hash_partial(partial: &Path) -> Result<(Sha256, u64)> {
+    let mut hasher = Sha256::new();
+    let mut file = match fs::File::open(partial).await {
+        Ok(file) => file,
+        Err(_) => return Ok((hasher, 0)),
+    };
+
+    let mut len = 0;
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        len += n as u64;
+    }
+    Ok((hasher, len))
+}
+
+/// A strong `ETag`, or failing that `Last-Modified`, to send as `If-Range`.
+fn response_validator(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED).and_then(|v| v.to_str().ok()))
+        .map(str::to_string)
+}
+
+fn content_range(response: &Response) -> Option<&str> {
+    response.headers().get(CONTENT_RANGE)?.to_str().ok()
+}
+
+/// The first byte of a `Content-Range: bytes <start>-<end>/<len>` header.
+fn range_start(content_range: &str) -> Option<u64> {
+    let range = content_range.strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
+
+/// Errors worth retrying: dropped connections and server-side failures.
+fn is_transient(e: &Error) -> bool {
+    match e {
+        Error::Http(e) => match e.status() {
+            Some(status) => status.is_server_error(),
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    const URL: &str =
+        "https://static.rust-lang.org/dist/rustc-nightly-x86_64-unknown-linux-gnu.tar.gz";
+    const SOURCE: &str = concat!(
+        "https://static.rust-lang.org/dist/rustc-nightly-x86_64-unknown-linux-gnu.tar.gz\n",
+        "\"abc\"\n"
+    );
+
+    fn action(source: &str, offset: u64, status: u16, content_range: Option<&str>) -> Resume {
+        let status = StatusCode::from_u16(status).unwrap();
+        resume_action(source, URL, offset, status, content_range)
+    }
+
+    #[test]
+    fn resume() {
+        assert_eq!(
+            action(SOURCE, 100, 206, Some("bytes 100-199/200")),
+            Resume::Append
+        );
+        assert_eq!(
+            action(SOURCE, 100, 206, Some("bytes 0-199/200")),
+            Resume::Refetch
+        );
+        assert_eq!(action(SOURCE, 100, 206, None), Resume::Refetch);
+        // The server ignored the range, or the file changed.
+        assert_eq!(action(SOURCE, 100, 200, None), Resume::Restart);
+        assert_eq!(action(SOURCE, 100, 416, None), Resume::Refetch);
+        assert_eq!(action(SOURCE, 0, 200, None), Resume::Restart);
+        assert_eq!(
+            action(SOURCE, 0, 206, Some("bytes 0-99/200")),
+            Resume::Error
+        );
+        assert_eq!(action(SOURCE, 100, 503, None), Resume::Restart);
+    }
+
+    #[test]
+    fn partial_of_another_url() {
+        let other = SOURCE.replace("dist/", "dist/2024-02-01/");
+        assert_eq!(
+            action(&other, 100, 206, Some("bytes 100-199/200")),
+            Resume::Error
+        );
+        assert_eq!(action(&other, 0, 200, None), Resume::Restart);
+        assert_eq!(action("", 100, 200, None), Resume::Restart);
+    }
+
+    #[test]
+    fn validators() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(response_validator(&headers), None);
+
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Thu, 08 Feb 2024 00:00:00 GMT"),
+        );
+        headers.insert(ETAG, HeaderValue::from_static("W/\"abc\""));
+        assert_eq!(
+            response_validator(&headers).as_deref(),
+            Some("Thu, 08 Feb 2024 00:00:00 GMT")
+        );
+
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+        assert_eq!(response_validator(&headers).as_deref(), Some("\"abc\""));
+    }
+
+    #[test]
+    fn content_ranges() {
+        assert_eq!(range_start("bytes 100-199/200"), Some(100));
+        assert_eq!(range_start("bytes 0-0/*"), Some(0));
+        assert_eq!(range_start("bytes */200"), None);
+        assert_eq!(range_start("items 100-199/200"), None);
+    }
+}
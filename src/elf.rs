@@ -0,0 +1,214 @@
+//! Just enough ELF parsing to tell what a binary was built for.
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+pub const EM_386: u16 = 3;
+pub const EM_MIPS: u16 = 8;
+pub const EM_PPC: u16 = 20;
+pub const EM_PPC64: u16 = 21;
+pub const EM_S390: u16 = 22;
+pub const EM_ARM: u16 = 40;
+pub const EM_SPARCV9: u16 = 43;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+pub const EM_RISCV: u16 = 243;
+pub const EM_LOONGARCH: u16 = 258;
+
+/// `e_flags` bit marking an ARM binary as using the hard-float ABI.
+pub const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+
+const PT_INTERP: u32 = 3;
+
+/// Largest program header table Linux will load, in bytes.
+const MAX_PHDRS_SIZE: usize = 65536;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    pub is_64: bool,
+    pub little_endian: bool,
+    pub machine: u16,
+    pub flags: u32,
+    /// The dynamic loader, e.g. `/lib/ld-musl-x86_64.so.1`, `None` for
+    /// static binaries.
+    pub interpreter: Option<String>,
+}
+
+impl Elf {
+    /// Read the header of the ELF file at `path`, `None` if it isn't one.
+    pub fn read(path: &Path) -> Option<Self> {
+        Elf::parse(&mut File::open(path).ok()?)
+    }
+
+    /// Read the header of the ELF file in `file`, `None` if it isn't one.
+    pub fn parse<R: Read + Seek>(file: &mut R) -> Option<Self> {
+        let mut header = [0u8; 64];
+        file.read_exact(&mut header[..52]).ok()?;
+        if header[..4] != *b"\x7fELF" {
+            return None;
+        }
+
+        let is_64 = header[4] == 2;
+        let little_endian = header[5] == 1;
+        if is_64 {
+            file.read_exact(&mut header[52..]).ok()?;
+        }
+        let fields = Fields { little_endian };
+
+        let machine = fields.u16(&header[18..]);
+        let (flags, phoff, phentsize, phnum) = if is_64 {
+            (
+                fields.u32(&header[48..]),
+                fields.u64(&header[32..]),
+                fields.u16(&header[54..]),
+                fields.u16(&header[56..]),
+            )
+        } else {
+            (
+                fields.u32(&header[36..]),
+                fields.u32(&header[28..]) as u64,
+                fields.u16(&header[42..]),
+                fields.u16(&header[44..]),
+            )
+        };
+
+        // Program headers too small for the fields read below, or more of
+        // them than the kernel would load, aren't worth trusting.
+        let min_phentsize = if is_64 { 56 } else { 32 };
+        if phentsize < min_phentsize || phnum as usize * phentsize as usize > MAX_PHDRS_SIZE {
+            return None;
+        }
+
+        let mut interpreter = None;
+        let mut entry = vec![0u8; phentsize as usize];
+        for i in 0..phnum as u64 {
+            let offset = phoff.checked_add(i * phentsize as u64)?;
+            file.seek(SeekFrom::Start(offset)).ok()?;
+            file.read_exact(&mut entry).ok()?;
+            if fields.u32(&entry) != PT_INTERP {
+                continue;
+            }
+
+            let (offset, size) = if is_64 {
+                (fields.u64(&entry[8..]), fields.u64(&entry[32..]))
+            } else {
+                (
+                    fields.u32(&entry[4..]) as u64,
+                    fields.u32(&entry[16..]) as u64,
+                )
+            };
+            let mut path = vec![0u8; size.min(4096) as usize];
+            file.seek(SeekFrom::Start(offset)).ok()?;
+            file.read_exact(&mut path).ok()?;
+            let path = String::from_utf8_lossy(&path);
+            interpreter = Some(path.trim_end_matches('\0').to_string());
+            break;
+        }
+
+        Some(Elf {
+            is_64,
+            little_endian,
+            machine,
+            flags,
+            interpreter,
+        })
+    }
+}
+
+/// Reads header fields in the file's byte order.
+struct Fields {
+    little_endian: bool,
+}
+
+impl Fields {
+    fn u16(&self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        }
+    }
+
+    fn u32(&self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        }
+    }
+
+    fn u64(&self, bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&bytes[..8]);
+        if self.little_endian {
+            u64::from_le_bytes(buf)
+        } else {
+            u64::from_be_bytes(buf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A little-endian 64-bit x86_64 executable whose only program header
+    /// is a `PT_INTERP` naming `interpreter`.
+    fn elf64(interpreter: &str, phentsize: u16, phnum: u16) -> Vec<u8> {
+        let mut file = vec![0u8; 64 + 56];
+        file[..4].copy_from_slice(b"\x7fELF");
+        file[4] = 2;
+        file[5] = 1;
+        file[18..20].copy_from_slice(&EM_X86_64.to_le_bytes());
+        file[32..40].copy_from_slice(&64u64.to_le_bytes());
+        file[54..56].copy_from_slice(&phentsize.to_le_bytes());
+        file[56..58].copy_from_slice(&phnum.to_le_bytes());
+
+        let phdr = &mut file[64..];
+        phdr[..4].copy_from_slice(&PT_INTERP.to_le_bytes());
+        phdr[8..16].copy_from_slice(&120u64.to_le_bytes());
+        phdr[32..40].copy_from_slice(&(interpreter.len() as u64 + 1).to_le_bytes());
+        file.extend_from_slice(interpreter.as_bytes());
+        file.push(0);
+        file
+    }
+
+    #[test]
+    fn interpreter() {
+        let file = elf64("/lib/ld-musl-x86_64.so.1", 56, 1);
+        let elf = Elf::parse(&mut Cursor::new(file)).unwrap();
+        assert!(elf.is_64);
+        assert!(elf.little_endian);
+        assert_eq!(elf.machine, EM_X86_64);
+        assert_eq!(elf.interpreter.as_deref(), Some("/lib/ld-musl-x86_64.so.1"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(Elf::parse(&mut Cursor::new(b"#!/bin/sh\n".to_vec())), None);
+        let mut file = elf64("/lib/ld-linux-x86-64.so.2", 56, 1);
+        file.truncate(60);
+        assert_eq!(Elf::parse(&mut Cursor::new(file)), None);
+
+        // Program headers too small to hold the fields read from them.
+        let file = elf64("/lib/ld-linux-x86-64.so.2", 8, 1);
+        assert_eq!(Elf::parse(&mut Cursor::new(file)), None);
+        // More program headers than fit in the kernel's limit.
+        let file = elf64("/lib/ld-linux-x86-64.so.2", 56, u16::MAX);
+        assert_eq!(Elf::parse(&mut Cursor::new(file)), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn own_binary() {
+        let elf = Elf::read(Path::new("/proc/self/exe")).unwrap();
+        assert_eq!(elf.is_64, cfg!(target_pointer_width = "64"));
+        assert_eq!(elf.little_endian, cfg!(target_endian = "little"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use crate::triple::InvalidTriple;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Http(reqwest::Error),
+    Install(String),
+    Manifest(String),
+    Toolchain(String),
+    Checksum {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+    Signature(String),
+    Config(String),
+    Offline(String),
+    Triple(InvalidTriple),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Http(e) => write!(f, "download failed: {}", e),
+            Error::Install(msg) => write!(f, "install failed: {}", msg),
+            Error::Manifest(msg) => write!(f, "invalid channel manifest: {}", msg),
+            Error::Toolchain(msg) => write!(f, "{}", msg),
+            Error::Checksum {
+                file,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "checksum mismatch for {}: expected sha256 {}, got {}",
+                file, expected, actual
+            ),
+            Error::Signature(msg) => write!(f, "signature verification failed: {}", msg),
+            Error::Config(msg) => write!(f, "invalid config: {}", msg),
+            Error::Offline(msg) => write!(f, "offline: {}", msg),
+            Error::Triple(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Http(e) => Some(e),
+            Error::Triple(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<InvalidTriple> for Error {
+    fn from(e: InvalidTriple) -> Self {
+        Error::Triple(e)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
@@ -0,0 +1,285 @@
+//! Layout of the get-rust home directory:
+//!
+//! ```text
+//! ~/.get-rust/
+//!     bin/
+//!         cargo, rustc, ...
+//!     config.toml
+//!     current -> toolchains/stable-x86_64-unknown-linux-gnu
+//!     downloads/
+//!     keys/
+//!     overrides.toml
+//!     tmp/
+//!     toolchains/
+//!         stable-x86_64-unknown-linux-gnu/
+//!         1.70.0-x86_64-unknown-linux-gnu/
+//! ```
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use semver::{Version, VersionReq};
+
+use crate::channel::ToolchainSpec;
+use crate::error::{Error, Result};
+use crate::targets;
+use crate::triple::TargetTriple;
+
+/// The directory get-rust keeps its own state in.
+///
+/// `GET_RUST_HOME` takes precedence, otherwise `~/.get-rust` is used.
+pub fn get_rust_home() -> PathBuf {
+    if let Some(home) = std::env::var_os("GET_RUST_HOME") {
+        return PathBuf::from(home);
+    }
+
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".get-rust")
+}
+
+/// Where archives are kept while they are being downloaded.
+pub fn downloads_dir() -> PathBuf {
+    get_rust_home().join("downloads")
+}
+
+/// Where archives are unpacked before being installed.
+pub fn staging_dir(name: &str) -> PathBuf {
+    get_rust_home().join("tmp").join(name)
+}
+
+pub fn toolchains_dir() -> PathBuf {
+    get_rust_home().join("toolchains")
+}
+
+pub fn toolchain_dir(name: &str) -> PathBuf {
+    toolchains_dir().join(name)
+}
+
+/// The symlink pointing at the default toolchain.
+pub fn current_link() -> PathBuf {
+    get_rust_home().join("current")
+}
+
+/// Name of the toolchain `spec` installs for `triple`, e.g.
+/// `stable-x86_64-unknown-linux-gnu`.
+pub fn toolchain_name(spec: &str, triple: &TargetTriple) -> String {
+    format!("{}-{}", spec, triple)
+}
+
+/// The triple a toolchain in the store was installed for, the longest known
+/// target its name ends in.
+pub fn toolchain_triple(name: &str) -> Option<&'static str> {
+    targets::TARGETS
+        .iter()
+        .map(|t| t.triple)
+        .filter(|t| name.strip_suffix(t).is_some_and(|rest| rest.ends_with('-')))
+        .max_by_key(|t| t.len())
+}
+
+/// Names of every toolchain in the store, sorted.
+pub fn installed_toolchains() -> Result<Vec<String>> {
+    toolchains_in(&toolchains_dir())
+}
+
+/// Names of the toolchains in the store directory `root`, sorted.
+fn toolchains_in(root: &Path) -> Result<Vec<String>> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Whether `name` can be a toolchain's directory in the store: a single
+/// path component other than `.` and `..`, with no characters Windows
+/// doesn't allow in file names.
+pub fn is_valid_toolchain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    !name.contains(['/', '\\', ':', '<', '>', '"', '|', '?', '*'])
+        && matches!(components.next(), Some(Component::Normal(c)) if c == name)
+        && components.next().is_none()
+}
+
+/// Find an installed toolchain by its full name, or by its short name with
+/// the host triple left off (`stable` for `stable-x86_64-unknown-linux-gnu`).
+/// Partial versions and requirements like `1.76` or `>=1.70` find the newest
+/// matching release, which is what they were installed as.
+///
+/// Names that aren't [valid](is_valid_toolchain_name) are never looked up
+/// as directories, so they can't point outside the store.
+pub fn resolve_toolchain(name: &str) -> Option<String> {
+    let host = TargetTriple::get_with_no_rust_installed();
+    resolve_toolchain_in(&toolchains_dir(), name, &host)
+}
+
+/// Like [`resolve_toolchain`], but in the store directory `root`, with
+/// short names completed with `host`.
+pub fn resolve_toolchain_in(root: &Path, name: &str, host: &TargetTriple) -> Option<String> {
+    if is_valid_toolchain_name(name) {
+        if root.join(name).is_dir() {
+            return Some(name.to_string());
+        }
+        let full = toolchain_name(name, host);
+        if root.join(&full).is_dir() {
+            return Some(full);
+        }
+    }
+
+    let req = match name.parse() {
+        Ok(ToolchainSpec::Minor(major, minor)) => {
+            VersionReq::parse(&format!("~{}.{}", major, minor)).ok()?
+        }
+        Ok(ToolchainSpec::Requirement(req)) => req,
+        _ => return None,
+    };
+    let suffix = format!("-{}", host);
+    toolchains_in(root)
+        .ok()?
+        .into_iter()
+        .filter_map(|name| {
+            let version = Version::parse(name.strip_suffix(&suffix)?).ok()?;
+            req.matches(&version).then_some((version, name))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+/// Like [`resolve_toolchain`], but an error if it isn't installed.
+pub fn require_toolchain(name: &str) -> Result<String> {
+    resolve_toolchain(name).ok_or_else(|| {
+        if is_valid_toolchain_name(name) || name.parse::<ToolchainSpec>().is_ok() {
+            Error::Toolchain(format!("toolchain `{}` is not installed", name))
+        } else {
+            Error::Toolchain(format!("`{}` is not a valid toolchain name", name))
+        }
+    })
+}
+
+/// The toolchain `current` points at.
+pub fn default_toolchain() -> Option<String> {
+    let target = fs::read_link(current_link()).ok()?;
+    let name = target.file_name()?.to_string_lossy().into_owned();
+    if toolchain_dir(&name).is_dir() {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// Point `current` at an installed toolchain.
+///
+/// The new link is created next to the old one and renamed over it, so the
+/// default is never missing or half-updated.
+pub fn set_default_toolchain(name: &str) -> Result<()> {
+    let name = require_toolchain(name)?;
+
+    let link = current_link();
+    let tmp = get_rust_home().join(format!("current.{}", std::process::id()));
+    let _ = fs::remove_file(&tmp);
+    symlink_dir(&Path::new("toolchains").join(&name), &tmp)?;
+    fs::rename(&tmp, &link)?;
+    Ok(())
+}
+
+/// Remove `current`, leaving no default toolchain.
+pub fn clear_default_toolchain() -> Result<()> {
+    match fs::remove_file(current_link()) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triple_of_toolchain() {
+        assert_eq!(
+            toolchain_triple("stable-x86_64-unknown-linux-gnu"),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(
+            toolchain_triple("nightly-2024-02-01-x86_64-unknown-linux-gnux32"),
+            Some("x86_64-unknown-linux-gnux32")
+        );
+        assert_eq!(toolchain_triple("x86_64-unknown-linux-gnu"), None);
+        assert_eq!(toolchain_triple("my-toolchain"), None);
+    }
+
+    #[test]
+    fn valid_toolchain_names() {
+        for name in ["stable", "1.76.0-x86_64-unknown-linux-gnu", "my.toolchain"] {
+            assert!(is_valid_toolchain_name(name), "{}", name);
+        }
+        for name in ["", ".", "..", "a/b", "a\\b", "/stable", "C:x", "stable?"] {
+            assert!(!is_valid_toolchain_name(name), "{}", name);
+        }
+    }
+
+    /// A temporary store directory, removed when dropped.
+    struct TempStore(PathBuf);
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let store =
+            TempStore(std::env::temp_dir().join(format!("get-rust-store-{}", std::process::id())));
+        let root = &store.0;
+        let host = TargetTriple::from_target_triple("x86_64-unknown-linux-gnu");
+        for name in ["stable", "1.70.0", "1.76.0", "1.76.2", "1.77.1"] {
+            fs::create_dir_all(root.join(toolchain_name(name, &host))).unwrap();
+        }
+        // Another host's toolchains are only found by their full name.
+        let other = TargetTriple::from_target_triple("aarch64-apple-darwin");
+        fs::create_dir_all(root.join(toolchain_name("1.78.0", &other))).unwrap();
+        fs::create_dir_all(root.join("a/b")).unwrap();
+
+        let resolve = |name: &str| resolve_toolchain_in(root, name, &host);
+        let full = |name: &str| Some(toolchain_name(name, &host));
+
+        assert_eq!(resolve("stable"), full("stable"));
+        assert_eq!(resolve(&toolchain_name("stable", &host)), full("stable"));
+        assert_eq!(resolve("1.76"), full("1.76.2"));
+        assert_eq!(resolve("1.76.0"), full("1.76.0"));
+        assert_eq!(resolve(">=1.70"), full("1.77.1"));
+        assert_eq!(resolve("1.75"), None);
+        assert_eq!(resolve("beta"), None);
+        assert_eq!(
+            resolve("1.78.0-aarch64-apple-darwin"),
+            Some("1.78.0-aarch64-apple-darwin".to_string())
+        );
+        for name in ["..", "a/b", "C:x", "../toolchains/stable"] {
+            assert_eq!(resolve(name), None, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,1053 @@
+use std::{
+    fs,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use indicatif::ProgressBar;
+
+use crate::channel::{self, Component, Manifest, PackageTarget, Source, ToolchainSpec};
+use crate::checksum;
+use crate::dist::{self, DistServers};
+use crate::download;
+use crate::error::{Error, Result};
+use crate::home;
+use crate::installer;
+use crate::progress;
+use crate::proxy;
+use crate::signature::{self, Keyring};
+use crate::triple::TargetTriple;
+
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    pub triple: TargetTriple,
+    pub version: String,
+    pub prefix: Option<PathBuf>,
+    /// Components to install, the manifest's `default` profile if empty.
+    pub components: Vec<String>,
+    /// Profile to install, e.g. `minimal`; `components` are added to it.
+    pub profile: Option<String>,
+    /// Components to leave out.
+    pub without: Vec<String>,
+    /// Targets to install the standard library for besides `triple`.
+    pub targets: Vec<TargetTriple>,
+    /// Public key files trusted in addition to the Rust release key.
+    pub trusted_keys: Vec<PathBuf>,
+    pub dist: DistServers,
+    /// Install this local `.tar.gz` instead of resolving `version`.
+    pub archive: Option<PathBuf>,
+}
+
+impl InstallOptions {
+    pub fn new(triple: TargetTriple, version: String) -> Self {
+        InstallOptions {
+            triple,
+            version,
+            prefix: None,
+            components: Vec::new(),
+            profile: None,
+            without: Vec::new(),
+            targets: Vec::new(),
+            trusted_keys: Vec::new(),
+            dist: DistServers::default(),
+            archive: None,
+        }
+    }
+
+    /// Name of the toolchain in the store, e.g. `stable-x86_64-unknown-linux-gnu`,
+    /// or `1.76.0-x86_64-unknown-linux-gnu` for `rust-1.76.0-x86_64-unknown-linux-gnu.tar.gz`.
+    ///
+    /// Versions are named after the release `manifest` resolved them to, so
+    /// `1.76`, `1.76.0` and `>=1.70` all install `1.76.0-x86_64-unknown-linux-gnu`
+    /// when that's the newest match. Channels keep their own name.
+    pub fn toolchain_name(&self, manifest: Option<&Manifest>) -> String {
+        if let Some(archive) = &self.archive {
+            let name = archive_stem(&archive_name(&archive.to_string_lossy()));
+            return name.strip_prefix("rust-").unwrap_or(&name).to_string();
+        }
+
+        let is_channel = matches!(self.version.parse(), Ok(ToolchainSpec::Channel { .. }));
+        let release = manifest
+            .filter(|_| !is_channel)
+            .and_then(|m| m.rust_version())
+            .map(|v| v.to_string());
+        home::toolchain_name(release.as_deref().unwrap_or(&self.version), &self.triple)
+    }
+
+    /// The explicit prefix, or the toolchain's directory in the store.
+    pub fn prefix(&self, manifest: Option<&Manifest>) -> PathBuf {
+        self.prefix
+            .clone()
+            .unwrap_or_else(|| home::toolchain_dir(&self.toolchain_name(manifest)))
+    }
+}
+
+pub async fn install_rust(options: &InstallOptions) -> Result<()> {
+    let keyring = Keyring::with_trusted_keys(&options.trusted_keys)?;
+
+    let name = match &options.archive {
+        Some(path) => install_local_archive(path, options, &keyring).await?,
+        None => install_from_manifest(options, &keyring).await?,
+    };
+
+    if options.prefix.is_none() {
+        // The first toolchain installed into the store becomes the default.
+        if home::default_toolchain().is_none() {
+            home::set_default_toolchain(&name)?;
+            println!("Default toolchain set to {}", name);
+        }
+
+        proxy::install_proxies()?;
+        if !proxy::bin_dir_on_path() {
+            println!(
+                "Add {} to your PATH to use the installed toolchains",
+                proxy::bin_dir().display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve `options.version` and install the selected components, fetching
+/// only their own archives rather than the combined `rust-*` one. Returns
+/// the name of the toolchain.
+async fn install_from_manifest(options: &InstallOptions, keyring: &Keyring) -> Result<String> {
+    let target = options.triple.to_string();
+    let spec: ToolchainSpec = options.version.parse()?;
+
+    let source = Source {
+        dist: &options.dist,
+        keyring,
+    };
+    let manifest = channel::resolve(&spec, source).await?;
+    check_target(&manifest, "rust", &options.triple)?;
+    for triple in &options.targets {
+        check_target(&manifest, "rust-std", triple)?;
+    }
+    let version = manifest
+        .rust_version()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| spec.to_string());
+    println!(
+        "Installing Rust {} ({}) for target: {}",
+        version, manifest.date, target
+    );
+
+    let name = options.toolchain_name(Some(&manifest));
+    let prefix = options.prefix(Some(&manifest));
+    let previous = Manifest::load_installed(&prefix).ok();
+
+    let components = match &previous {
+        // Another release is installed here. Everything it had is installed
+        // again from this one, so none of its files are left behind.
+        Some(old) if old.date != manifest.date => {
+            let (names, targets) =
+                installed_selection(old, &installer::installed_components(&prefix)?, &target);
+            let mut options = options.clone();
+            options.components.extend(
+                names
+                    .into_iter()
+                    .filter(|c| manifest.get(&manifest.rename(c), &target).is_some()),
+            );
+            options.targets.extend(
+                targets
+                    .into_iter()
+                    .filter(|t| manifest.get("rust-std", &t.to_string()).is_some()),
+            );
+            select_components(&manifest, &target, &options)?
+        }
+        // Only fill in what's missing when the same release is installed.
+        Some(_) => {
+            let installed = installer::installed_components(&prefix)?;
+            let mut components = select_components(&manifest, &target, options)?;
+            components.retain(|c| {
+                !installed
+                    .iter()
+                    .any(|i| component_matches(i, &c.pkg, &c.target))
+            });
+            if components.is_empty() {
+                println!("Rust {} is up to date in {}", version, prefix.display());
+                return Ok(name);
+            }
+            components
+        }
+        None => select_components(&manifest, &target, options)?,
+    };
+
+    // Every component archive is unpacked side by side in here, e.g.
+    // `rust-1.76.0-x86_64-unknown-linux-gnu/rustc-1.76.0-x86_64-unknown-linux-gnu`.
+    let staging = home::staging_dir(&format!("rust-{}-{}", version, target));
+    let result = async {
+        let mut package_dirs = Vec::new();
+        for component in &components {
+            let package = manifest
+                .get(&component.pkg, &component.target)
+                .filter(|t| t.url.is_some())
+                .ok_or_else(|| {
+                    Error::Toolchain(format!(
+                        "{} is not available for {}",
+                        component.pkg, component.target
+                    ))
+                })?;
+
+            let archive = fetch_package(&options.dist, keyring, package).await?;
+            package_dirs.push(unpack_package(&archive, &staging)?);
+            fs::remove_file(&archive)?;
+        }
+
+        // The old release is only removed once the new one is ready to install.
+        if previous.is_some_and(|old| old.date != manifest.date) {
+            installer::remove_all(&prefix)?;
+        }
+        let mut installed = Vec::new();
+        for package_dir in &package_dirs {
+            installed.extend(install_package(package_dir, &prefix, |_| true)?);
+        }
+        manifest.save_installed(&prefix)?;
+        Ok::<_, Error>(installed)
+    }
+    .await;
+    let cleanup = remove_staging(&staging);
+    let installed = result?;
+    cleanup?;
+
+    println!(
+        "Installed {} into {}",
+        installed.join(", "),
+        prefix.display()
+    );
+    Ok(name)
+}
+
+/// Add the standard library for more targets to the toolchain installed in
+/// `options.prefix(None)`, using the manifest it was installed from.
+pub async fn add_targets(options: &InstallOptions, targets: &[TargetTriple]) -> Result<()> {
+    let prefix = options.prefix(None);
+    let manifest = Manifest::load_installed(&prefix)?;
+    for triple in targets {
+        check_target(&manifest, "rust-std", triple)?;
+    }
+    let version = manifest
+        .rust_version()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| manifest.date.clone());
+    let keyring = Keyring::with_trusted_keys(&options.trusted_keys)?;
+    let staging = home::staging_dir(&format!("rust-{}-{}", version, options.triple));
+
+    let result = async {
+        for triple in targets {
+            let target = triple.to_string();
+            let package = manifest
+                .get("rust-std", &target)
+                .filter(|t| t.url.is_some())
+                .ok_or_else(|| {
+                    Error::Toolchain(format!(
+                        "rust-std is not available for {} in Rust {}",
+                        target, version
+                    ))
+                })?;
+
+            println!("Adding target {} to Rust {}", target, version);
+            let archive = fetch_package(&options.dist, &keyring, package).await?;
+            let package_dir = unpack_package(&archive, &staging)?;
+            fs::remove_file(&archive)?;
+
+            // The package's manifest.in places everything under
+            // `lib/rustlib/<triple>`.
+            install_package(&package_dir, &prefix, |_| true)?;
+        }
+        Ok::<_, Error>(())
+    }
+    .await;
+    let cleanup = remove_staging(&staging);
+    result?;
+    cleanup?;
+
+    Ok(())
+}
+
+/// Check that `manifest` has `pkg` for `triple`. Releases can have targets
+/// rustc no longer knows, like `wasm32-wasi`, so only a triple missing from
+/// both is reported as invalid, with suggestions.
+fn check_target(manifest: &Manifest, pkg: &str, triple: &TargetTriple) -> Result<()> {
+    if manifest.get(pkg, &triple.to_string()).is_some() {
+        return Ok(());
+    }
+    triple.validate()?;
+    Err(Error::Toolchain(format!(
+        "{} is not available for {} in Rust {}",
+        pkg, triple, manifest.date
+    )))
+}
+
+/// Pick the manifest components to install for `target`, plus `rust-std`
+/// for each of `options.targets`.
+///
+/// Names are accepted before or after renaming, e.g. `clippy` or
+/// `clippy-preview`. Components of a profile that don't exist for `target`
+/// (like `rust-mingw` off Windows) are skipped, ones asked for explicitly are
+/// an error.
+fn select_components(
+    manifest: &Manifest,
+    target: &str,
+    options: &InstallOptions,
+) -> Result<Vec<Component>> {
+    let rust = manifest.get("rust", target).ok_or_else(|| {
+        Error::Toolchain(format!(
+            "Rust {} is not available for {}",
+            manifest.date, target
+        ))
+    })?;
+    let available: Vec<&Component> = rust
+        .components
+        .iter()
+        .chain(&rust.extensions)
+        .filter(|c| c.target == target || c.target == "*")
+        .filter(|c| manifest.get(&c.pkg, &c.target).is_some())
+        .collect();
+
+    let profile: Vec<String> = if options.components.is_empty() || options.profile.is_some() {
+        let name = options.profile.as_deref().unwrap_or("default");
+        match manifest.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None if name != "default" => {
+                let mut names: Vec<&str> = manifest.profiles.keys().map(|k| k.as_str()).collect();
+                names.sort();
+                return Err(Error::Toolchain(format!(
+                    "no profile `{}` in Rust {}, available: {}",
+                    name,
+                    manifest.date,
+                    names.join(", ")
+                )));
+            }
+            None => rust.components.iter().map(|c| c.pkg.clone()).collect(),
+        }
+    } else {
+        Vec::new()
+    };
+    let explicit: Vec<String> = options
+        .components
+        .iter()
+        .map(|c| manifest.rename(c))
+        .collect();
+    let without: Vec<String> = options.without.iter().map(|c| manifest.rename(c)).collect();
+
+    let wanted = profile
+        .iter()
+        .map(|name| (name, false))
+        .chain(explicit.iter().map(|name| (name, true)));
+    let mut selected: Vec<Component> = Vec::new();
+    for (name, explicit) in wanted.filter(|(name, _)| !without.contains(name)) {
+        if selected.iter().any(|c| &c.pkg == name) {
+            continue;
+        }
+        match available.iter().find(|c| &c.pkg == name) {
+            Some(component) => selected.push((*component).clone()),
+            None if explicit => {
+                let mut names: Vec<&str> = available.iter().map(|c| c.pkg.as_str()).collect();
+                names.sort();
+                names.dedup();
+                return Err(Error::Toolchain(format!(
+                    "no component `{}` for {}, available: {}",
+                    name,
+                    target,
+                    names.join(", ")
+                )));
+            }
+            None => {}
+        }
+    }
+
+    for triple in &options.targets {
+        let std_target = triple.to_string();
+        if selected
+            .iter()
+            .any(|c| c.pkg == "rust-std" && c.target == std_target)
+        {
+            continue;
+        }
+        if manifest.get("rust-std", &std_target).is_none() {
+            return Err(Error::Toolchain(format!(
+                "rust-std is not available for {} in Rust {}",
+                std_target, manifest.date
+            )));
+        }
+        selected.push(Component {
+            pkg: "rust-std".to_string(),
+            target: std_target,
+        });
+    }
+
+    if selected.is_empty() {
+        return Err(Error::Toolchain(format!(
+            "nothing to install for {} from Rust {}",
+            target, manifest.date
+        )));
+    }
+    Ok(selected)
+}
+
+/// Install a local archive, checked against the `.sha256` and `.asc` files
+/// next to it. Returns the name of the toolchain.
+async fn install_local_archive(
+    path: &Path,
+    options: &InstallOptions,
+    keyring: &Keyring,
+) -> Result<String> {
+    println!("Installing Rust from {}", path.display());
+
+    let path = fs::canonicalize(path)
+        .map_err(|_| Error::Offline(format!("{} does not exist", path.display())))?;
+    let urls = vec![dist::file_url(&path)];
+
+    let pb = progress::spinner();
+    pb.set_message("Verifying...");
+
+    let expected = checksum::fetch_sha256(&options.dist, &urls).await?;
+    let digest = checksum::sha256_file(&path)?;
+    checksum::verify_digest(&archive_name(&urls[0]), &digest, &expected)?;
+
+    let asc = signature::fetch_signature(&options.dist, &urls).await?;
+    keyring.verify_file(&path, &asc)?;
+
+    pb.finish_and_clear();
+
+    // Unpacks to e.g. `rust-1.76.0-x86_64-unknown-linux-gnu/rust-1.76.0-x86_64-unknown-linux-gnu`.
+    let staging = home::staging_dir(&archive_stem(&archive_name(&urls[0])));
+    let name = options.toolchain_name(None);
+    let prefix = options.prefix(None);
+    let result = (|| {
+        let package_dir = unpack_package(&path, &staging)?;
+
+        let target = options.triple.to_string();
+        let available = installer::package_components(&package_dir)?;
+        for wanted in &options.components {
+            if !available
+                .iter()
+                .any(|c| component_matches(c, wanted, &target))
+            {
+                return Err(Error::Install(format!(
+                    "{} has no component `{}`, available: {}",
+                    path.display(),
+                    wanted,
+                    available.join(", ")
+                )));
+            }
+        }
+
+        install_package(&package_dir, &prefix, |component| {
+            (options.components.is_empty()
+                || options
+                    .components
+                    .iter()
+                    .any(|c| component_matches(component, c, &target)))
+                && !options
+                    .without
+                    .iter()
+                    .any(|c| component_matches(component, c, &target))
+        })
+    })();
+    let cleanup = remove_staging(&staging);
+    let installed = result?;
+    cleanup?;
+
+    println!(
+        "Installed {} into {}",
+        installed.join(", "),
+        prefix.display()
+    );
+    Ok(name)
+}
+
+/// Whether an installer component name, which for target-specific packages
+/// carries the triple (`rust-std-x86_64-unknown-linux-gnu`), is the one
+/// the user called `wanted`.
+fn component_matches(component: &str, wanted: &str, target: &str) -> bool {
+    component == wanted
+        || component == format!("{}-{}", wanted, target)
+        || component == format!("{}-preview", wanted)
+}
+
+/// Download a package archive and verify its checksum and signature.
+async fn fetch_package(
+    dist: &DistServers,
+    keyring: &Keyring,
+    package: &PackageTarget,
+) -> Result<PathBuf> {
+    let download_url = package.url.clone().unwrap_or_default();
+    let urls = dist.mirror_urls(&download_url);
+
+    // Download the file
+    let archive_path = home::downloads_dir().join(archive_name(&download_url));
+    let bar = progress::download_bar(&archive_name(&download_url));
+    let digest = match download::download_file(dist, &urls, &archive_path, &bar).await {
+        Ok(digest) => {
+            bar.finish();
+            digest
+        }
+        Err(e) => {
+            bar.abandon_with_message("Failed to download");
+            return Err(e);
+        }
+    };
+
+    let pb = progress::spinner();
+    pb.set_message("Verifying...");
+
+    let expected = match &package.hash {
+        Some(hash) => hash.clone(),
+        None => checksum::fetch_sha256(dist, &urls).await?,
+    };
+    if let Err(e) = checksum::verify_digest(&archive_name(&download_url), &digest, &expected) {
+        let _ = fs::remove_file(&archive_path);
+        pb.set_message("Checksum mismatch");
+        pb.finish();
+        return Err(e);
+    }
+
+    let asc = signature::fetch_signature(dist, &urls).await?;
+    if let Err(e) = keyring.verify_file(&archive_path, &asc) {
+        let _ = fs::remove_file(&archive_path);
+        pb.set_message("Bad signature");
+        pb.finish();
+        return Err(e);
+    }
+
+    pb.finish_and_clear();
+    Ok(archive_path)
+}
+
+/// Unpack a package archive into `staging`, returning the directory it
+/// created, which has the same name as the archive.
+fn unpack_package(archive: &Path, staging: &Path) -> Result<PathBuf> {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let bar = progress::extract_bar(&name, fs::metadata(archive)?.len());
+    unpack_archive(archive, staging, &bar)?;
+    bar.finish();
+
+    Ok(staging.join(archive_stem(&name)))
+}
+
+/// Install the components of an unpacked package that pass `filter`,
+/// returning their names.
+fn install_package(
+    package_dir: &Path,
+    prefix: &Path,
+    filter: impl Fn(&str) -> bool,
+) -> Result<Vec<String>> {
+    let selected: Vec<String> = installer::package_components(package_dir)?
+        .into_iter()
+        .filter(|c| filter(c))
+        .collect();
+
+    let pb = progress::spinner();
+    for component in &selected {
+        pb.set_message(format!("Installing {}...", component));
+        if let Err(e) = installer::install_component(package_dir, component, prefix) {
+            pb.abandon_with_message(format!("Failed to install {}", component));
+            return Err(e);
+        }
+    }
+    pb.finish_and_clear();
+
+    Ok(selected)
+}
+
+/// Unpack a `.tar.gz` into `dest`, advancing `pb` by the compressed bytes
+/// read and counting entries in its prefix.
+fn unpack_archive(path: &Path, dest: &Path, pb: &ProgressBar) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    let file = pb.wrap_read(fs::File::open(path)?);
+    let mut archive = tar::Archive::new(GzDecoder::new(BufReader::new(file)));
+    for (count, entry) in archive.entries()?.enumerate() {
+        entry?.unpack_in(dest)?;
+        pb.set_prefix((count + 1).to_string());
+    }
+    Ok(())
+}
+
+fn archive_name(url: &str) -> String {
+    url.rsplit('/').next().unwrap_or(url).to_string()
+}
+
+fn archive_stem(name: &str) -> String {
+    name.strip_suffix(".tar.gz").unwrap_or(name).to_string()
+}
+
+/// Re-resolve every `stable`, `beta` and `nightly` toolchain in the store and
+/// install newer releases, keeping the components and targets they had.
+///
+/// The release being replaced is kept under its own name, e.g.
+/// `1.75.0-x86_64-unknown-linux-gnu` or `nightly-2024-02-01-x86_64-unknown-linux-gnu`,
+/// unless `prune` is set. `options` supplies the dist servers and keys.
+pub async fn update_toolchains(options: &InstallOptions, prune: bool) -> Result<()> {
+    let keyring = Keyring::with_trusted_keys(&options.trusted_keys)?;
+    let source = Source {
+        dist: &options.dist,
+        keyring: &keyring,
+    };
+
+    for name in home::installed_toolchains()? {
+        let Some((channel, triple)) = channel_toolchain(&name) else {
+            continue;
+        };
+        let dir = home::toolchain_dir(&name);
+        let Ok(old) = Manifest::load_installed(&dir) else {
+            println!("Skipping {}, it has no channel manifest", name);
+            continue;
+        };
+
+        let spec = ToolchainSpec::Channel {
+            name: channel.to_string(),
+            date: None,
+        };
+        let manifest = channel::resolve(&spec, source).await?;
+        if manifest.date <= old.date {
+            println!("{} is up to date", name);
+            continue;
+        }
+
+        let mut update = options.clone();
+        update.triple = TargetTriple::from_target_triple(triple);
+        update.version = channel.to_string();
+        update.prefix = None;
+        (update.components, update.targets) =
+            installed_selection(&old, &installer::installed_components(&dir)?, triple);
+
+        // Move the old release out of the way, and back again if the update
+        // fails.
+        let backup = home::staging_dir(&format!("{}.old", name));
+        let _ = fs::remove_dir_all(&backup);
+        fs::create_dir_all(home::staging_dir(""))?;
+        move_toolchain(&dir, &backup)?;
+
+        if let Err(e) = install_rust(&update).await {
+            let _ = fs::remove_dir_all(&dir);
+            move_toolchain(&backup, &dir)?;
+            return Err(e);
+        }
+
+        let kept = match old.rust_version() {
+            Some(version) if channel == "stable" => version.to_string(),
+            _ => format!("{}-{}", channel, old.date),
+        };
+        let kept = format!("{}-{}", kept, triple);
+        let kept_dir = home::toolchain_dir(&kept);
+        if prune || kept_dir.exists() {
+            uninstall_rust(&backup, Some(triple))?;
+            fs::remove_dir_all(&backup)?;
+        } else {
+            move_toolchain(&backup, &kept_dir)?;
+            println!("Kept the previous release as {}", kept);
+        }
+    }
+
+    Ok(())
+}
+
+/// Move an installed toolchain, updating the paths it has recorded.
+fn move_toolchain(from: &Path, to: &Path) -> Result<()> {
+    fs::rename(from, to)?;
+    installer::relocate(to, from)
+}
+
+/// Split `stable-x86_64-unknown-linux-gnu` into its channel and triple.
+/// Dated toolchains like `nightly-2024-02-01-...` never move, so aren't
+/// returned.
+fn channel_toolchain(name: &str) -> Option<(&str, &str)> {
+    let (channel, triple) = name.split_once('-')?;
+    let is_channel = ["stable", "beta", "nightly"].contains(&channel);
+    if !is_channel || triple.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((channel, triple))
+}
+
+/// The component names and extra targets to ask for to get the installer
+/// `components` of a toolchain installed from `manifest` again.
+fn installed_selection(
+    manifest: &Manifest,
+    components: &[String],
+    target: &str,
+) -> (Vec<String>, Vec<TargetTriple>) {
+    let mut names = Vec::new();
+    let mut targets = Vec::new();
+    let Some(rust) = manifest.get("rust", target) else {
+        return (names, targets);
+    };
+
+    for component in rust.components.iter().chain(&rust.extensions) {
+        if !components
+            .iter()
+            .any(|c| component_matches(c, &component.pkg, &component.target))
+        {
+            continue;
+        }
+        if component.pkg == "rust-std" && component.target != target {
+            targets.push(TargetTriple::from_target_triple(&component.target));
+        } else if !names.contains(&component.pkg) {
+            names.push(component.pkg.clone());
+        }
+    }
+    (names, targets)
+}
+
+/// Remove the toolchain installed in `prefix`, deleting exactly the files
+/// recorded for each component, and the staging directory an install of it
+/// for `triple` may have left behind. Without a `triple`, the one it was
+/// installed for is worked out from its components.
+pub fn uninstall_rust(prefix: &Path, triple: Option<&str>) -> Result<()> {
+    let components = installer::installed_components(prefix)?;
+    if components.is_empty() {
+        return Err(Error::Install(format!(
+            "no Rust installation found in {}",
+            prefix.display()
+        )));
+    }
+
+    let manifest = Manifest::load_installed(prefix).ok();
+    let version = manifest.as_ref().and_then(|m| m.rust_version());
+    let triple = match triple {
+        Some(triple) => Some(triple.to_string()),
+        None => installed_triple(prefix, manifest.as_ref(), &components),
+    };
+    match fs::remove_file(Manifest::installed_path(prefix)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let pb = progress::spinner();
+    pb.set_message("Removing...");
+    let removed = match installer::remove_all(prefix) {
+        Ok(removed) => removed,
+        Err(e) => {
+            pb.abandon_with_message("Failed to remove");
+            return Err(e);
+        }
+    };
+    pb.finish_and_clear();
+
+    if let (Some(version), Some(triple)) = (version, triple) {
+        remove_staging(&home::staging_dir(&format!("rust-{}-{}", version, triple)))?;
+    }
+
+    println!("Removed {} from {}", removed.join(", "), prefix.display());
+    Ok(())
+}
+
+/// The triple the toolchain in `prefix` with installer `components` was
+/// installed for: the only target it has `rust-std` for that rustc runs on,
+/// or failing that the only one with rustc's own `lib/rustlib/<triple>/bin`.
+fn installed_triple(
+    prefix: &Path,
+    manifest: Option<&Manifest>,
+    components: &[String],
+) -> Option<String> {
+    let hosts: Vec<&str> = components
+        .iter()
+        .filter_map(|c| c.strip_prefix("rust-std-"))
+        .filter(|t| manifest.is_none_or(|m| m.get("rustc", t).is_some()))
+        .collect();
+    if let [triple] = hosts[..] {
+        return Some(triple.to_string());
+    }
+    let rustlib = prefix.join("lib/rustlib");
+    let hosts: Vec<&str> = hosts
+        .into_iter()
+        .filter(|t| rustlib.join(t).join("bin").is_dir())
+        .collect();
+    match hosts[..] {
+        [triple] => Some(triple.to_string()),
+        _ => None,
+    }
+}
+
+/// Uninstall a toolchain from the store and delete its directory, clearing
+/// the default if it was the default.
+pub fn uninstall_toolchain(name: &str) -> Result<()> {
+    let name = home::require_toolchain(name)?;
+    let dir = home::toolchain_dir(&name);
+    if !home::installed_toolchains()?.contains(&name)
+        || dir.parent() != Some(home::toolchains_dir().as_path())
+    {
+        return Err(Error::Toolchain(format!(
+            "toolchain `{}` is not installed",
+            name
+        )));
+    }
+
+    // A toolchain with nothing recorded, e.g. from an interrupted install,
+    // is only removed if it looks like one.
+    if !installer::installed_components(&dir)?.is_empty() {
+        uninstall_rust(&dir, home::toolchain_triple(&name))?;
+    } else if !dir.join("lib/rustlib").is_dir() {
+        return Err(Error::Toolchain(format!(
+            "{} has no Rust installation, not removing it",
+            dir.display()
+        )));
+    }
+    fs::remove_dir_all(&dir)?;
+    println!("Uninstalled {}", name);
+
+    if home::default_toolchain().is_none() {
+        home::clear_default_toolchain()?;
+    }
+    Ok(())
+}
+
+/// Remove a staging directory like `tmp/rust-1.76.0-x86_64-unknown-linux-gnu`,
+/// if it's there.
+fn remove_staging(dir: &Path) -> Result<()> {
+    match fs::remove_dir_all(dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Run the `uninstall.sh` that `install.sh` left in `prefix`.
+pub async fn uninstall_legacy(prefix: &Path) -> Result<()> {
+    let script = prefix.join("lib/rustlib/uninstall.sh");
+    if !script.exists() {
+        return Err(Error::Install(format!(
+            "no Rust installation found in {}",
+            prefix.display()
+        )));
+    }
+
+    let status = tokio::process::Command::new(&script).status().await?;
+    if !status.success() {
+        return Err(Error::Install(format!(
+            "{} exited with {}",
+            script.display(),
+            status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "x86_64-unknown-linux-gnu";
+
+    fn package(name: &str, targets: &[&str]) -> String {
+        let mut toml = format!("[pkg.{}]\nversion = \"1.76.0\"\n", name);
+        for target in targets {
+            toml.push_str(&format!(
+                "[pkg.{}.target.\"{}\"]\navailable = true\nurl = \"https://example.com/{}-{}.tar.gz\"\n",
+                name, target, name, target
+            ));
+        }
+        toml
+    }
+
+    fn manifest() -> Manifest {
+        let mut toml = String::from(
+            r#"
+manifest-version = "2"
+date = "2024-02-08"
+
+[renames.clippy]
+to = "clippy-preview"
+
+[profiles]
+minimal = ["rustc", "cargo", "rust-std", "rust-mingw"]
+default = ["rustc", "cargo", "rust-std", "rust-mingw", "rust-docs", "clippy-preview"]
+
+[pkg.rust]
+version = "1.76.0"
+[pkg.rust.target.x86_64-unknown-linux-gnu]
+available = true
+components = [
+    { pkg = "rustc", target = "x86_64-unknown-linux-gnu" },
+    { pkg = "cargo", target = "x86_64-unknown-linux-gnu" },
+    { pkg = "rust-std", target = "x86_64-unknown-linux-gnu" },
+    { pkg = "rust-docs", target = "x86_64-unknown-linux-gnu" },
+]
+extensions = [
+    { pkg = "clippy-preview", target = "x86_64-unknown-linux-gnu" },
+    { pkg = "rust-src", target = "*" },
+    { pkg = "rust-std", target = "wasm32-unknown-unknown" },
+    { pkg = "rust-std", target = "wasm32-wasi" },
+]
+"#,
+        );
+        toml.push_str(&package("rustc", &[HOST]));
+        toml.push_str(&package("cargo", &[HOST]));
+        toml.push_str(&package(
+            "rust-std",
+            &[HOST, "wasm32-unknown-unknown", "wasm32-wasi"],
+        ));
+        toml.push_str(&package("rust-docs", &[HOST]));
+        toml.push_str(&package("clippy-preview", &[HOST]));
+        toml.push_str(&package("rust-src", &["*"]));
+        toml.push_str(&package("rust-mingw", &["x86_64-pc-windows-gnu"]));
+        Manifest::parse(&toml).unwrap()
+    }
+
+    fn options(configure: impl FnOnce(&mut InstallOptions)) -> InstallOptions {
+        let mut options =
+            InstallOptions::new(TargetTriple::from_target_triple(HOST), "1.76".to_string());
+        configure(&mut options);
+        options
+    }
+
+    fn select(options: &InstallOptions) -> Result<Vec<(String, String)>> {
+        Ok(select_components(&manifest(), HOST, options)?
+            .into_iter()
+            .map(|c| (c.pkg, c.target))
+            .collect())
+    }
+
+    fn names(selected: &[(String, String)]) -> Vec<&str> {
+        selected.iter().map(|(pkg, _)| pkg.as_str()).collect()
+    }
+
+    #[test]
+    fn default_profile() {
+        let selected = select(&options(|_| {})).unwrap();
+        // rust-mingw is only published for Windows, so it's skipped.
+        assert_eq!(
+            names(&selected),
+            ["rustc", "cargo", "rust-std", "rust-docs", "clippy-preview"]
+        );
+        assert!(selected.iter().all(|(_, target)| target == HOST));
+    }
+
+    #[test]
+    fn explicit_components() {
+        let selected = select(&options(|o| {
+            o.components = vec!["rustc".to_string(), "clippy".to_string()];
+        }))
+        .unwrap();
+        assert_eq!(names(&selected), ["rustc", "clippy-preview"]);
+
+        // Components come on top of an explicit profile.
+        let selected = select(&options(|o| {
+            o.profile = Some("minimal".to_string());
+            o.components = vec!["rust-src".to_string()];
+        }))
+        .unwrap();
+        assert_eq!(names(&selected), ["rustc", "cargo", "rust-std", "rust-src"]);
+        assert_eq!(selected[3].1, "*");
+    }
+
+    #[test]
+    fn without_and_targets() {
+        let selected = select(&options(|o| {
+            o.profile = Some("minimal".to_string());
+            o.without = vec!["cargo".to_string()];
+            o.targets = vec![
+                TargetTriple::from_target_triple("wasm32-unknown-unknown"),
+                TargetTriple::from_target_triple(HOST),
+            ];
+        }))
+        .unwrap();
+        assert_eq!(
+            selected,
+            [
+                ("rustc".to_string(), HOST.to_string()),
+                ("rust-std".to_string(), HOST.to_string()),
+                ("rust-std".to_string(), "wasm32-unknown-unknown".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn targets_of_the_release() {
+        let manifest = manifest();
+        let triple = TargetTriple::from_target_triple;
+
+        // Renamed since, but shipped by this release.
+        assert!(triple("wasm32-wasi").validate().is_err());
+        check_target(&manifest, "rust-std", &triple("wasm32-wasi")).unwrap();
+        check_target(&manifest, "rust", &triple(HOST)).unwrap();
+
+        match check_target(&manifest, "rust-std", &triple("aarch64-apple-darwin")) {
+            Err(Error::Toolchain(msg)) => assert!(msg.contains("not available"), "{}", msg),
+            other => panic!("{:?}", other),
+        }
+        match check_target(&manifest, "rust-std", &triple("x86_64-unknown-linux-gnux")) {
+            Err(Error::Triple(e)) => {
+                assert_eq!(e.suggestions.first().map(String::as_str), Some(HOST))
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn triple_of_installed_toolchain() {
+        let prefix =
+            std::env::temp_dir().join(format!("get-rust-installed-{}", std::process::id()));
+        let components = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let wasm = "wasm32-unknown-unknown";
+        let aarch64 = "aarch64-unknown-linux-gnu";
+
+        let one = components(&["rustc", "cargo", &format!("rust-std-{}", HOST)]);
+        assert_eq!(installed_triple(&prefix, None, &one).as_deref(), Some(HOST));
+        assert_eq!(
+            installed_triple(&prefix, None, &components(&["rustc"])),
+            None
+        );
+
+        // wasm32 has no rustc, so it can't be the host.
+        let cross = components(&[
+            "rustc",
+            &format!("rust-std-{}", HOST),
+            &format!("rust-std-{}", wasm),
+        ]);
+        assert_eq!(
+            installed_triple(&prefix, Some(&manifest()), &cross).as_deref(),
+            Some(HOST)
+        );
+        assert_eq!(installed_triple(&prefix, None, &cross), None);
+
+        let two_hosts = components(&[
+            "rustc",
+            &format!("rust-std-{}", aarch64),
+            &format!("rust-std-{}", HOST),
+        ]);
+        assert_eq!(installed_triple(&prefix, None, &two_hosts), None);
+        fs::create_dir_all(prefix.join("lib/rustlib").join(HOST).join("bin")).unwrap();
+        let triple = installed_triple(&prefix, None, &two_hosts);
+        fs::remove_dir_all(&prefix).unwrap();
+        assert_eq!(triple.as_deref(), Some(HOST));
+    }
+
+    #[test]
+    fn selection_errors() {
+        let missing = options(|o| o.components = vec!["miri".to_string()]);
+        let err = select(&missing).unwrap_err().to_string();
+        assert!(err.contains("no component `miri`"), "{}", err);
+
+        let mingw = options(|o| o.components = vec!["rust-mingw".to_string()]);
+        assert!(select(&mingw).is_err());
+
+        let profile = options(|o| o.profile = Some("complete".to_string()));
+        let err = select(&profile).unwrap_err().to_string();
+        assert!(err.contains("available: default, minimal"), "{}", err);
+
+        let target =
+            options(|o| o.targets = vec![TargetTriple::from_target_triple("aarch64-apple-darwin")]);
+        assert!(select(&target).is_err());
+
+        let nothing = options(|o| {
+            o.profile = Some("minimal".to_string());
+            o.without = ["rustc", "cargo", "rust-std"].map(String::from).to_vec();
+        });
+        let err = select(&nothing).unwrap_err().to_string();
+        assert!(err.contains("nothing to install"), "{}", err);
+
+        let other_host = select_components(&manifest(), "aarch64-apple-darwin", &options(|_| {}));
+        assert!(other_host.is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! A native implementation of the rust-installer format used by every archive
+//! on the dist server.
+//!
+//! An unpacked archive looks like
+//!
+//! ```text
+//! rust-1.76.0-x86_64-unknown-linux-gnu/
+//!     rust-installer-version
+//!     components            one component name per line
+//!     rustc/
+//!         manifest.in       `file:bin/rustc`, `dir:share/doc/rust`, ...
+//!         bin/rustc
+//!     cargo/
+//!         ...
+//! ```
+//!
+//! Installing a component copies everything listed in its `manifest.in` into
+//! the prefix and records the installed paths in
+//! `<prefix>/lib/rustlib/manifest-<component>`, the same file `install.sh`
+//! writes, so installs from either can be inspected and removed the same way.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+/// The rust-installer format version this module understands.
+const INSTALLER_VERSION: &str = "3";
+
+fn rustlib(prefix: &Path) -> PathBuf {
+    prefix.join("lib/rustlib")
+}
+
+fn manifest_file(prefix: &Path, component: &str) -> PathBuf {
+    rustlib(prefix).join(format!("manifest-{}", component))
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Components an unpacked archive in `dir` provides.
+pub fn package_components(dir: &Path) -> Result<Vec<String>> {
+    let version_file = dir.join("rust-installer-version");
+    if let Ok(version) = fs::read_to_string(&version_file) {
+        if version.trim() != INSTALLER_VERSION {
+            return Err(Error::Install(format!(
+                "{} uses rust-installer version {}, expected {}",
+                dir.display(),
+                version.trim(),
+                INSTALLER_VERSION
+            )));
+        }
+    }
+    read_lines(&dir.join("components"))
+}
+
+/// Components recorded as installed under `prefix`.
+pub fn installed_components(prefix: &Path) -> Result<Vec<String>> {
+    match read_lines(&rustlib(prefix).join("components")) {
+        Ok(components) => Ok(components),
+        Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn write_installed_components(prefix: &Path, components: &[String]) -> Result<()> {
+    let mut contents = String::new();
+    for component in components {
+        contents.push_str(component);
+        contents.push('\n');
+    }
+    fs::write(rustlib(prefix).join("components"), contents)?;
+    Ok(())
+}
+
+/// Install `component` from the unpacked archive in `dir` into `prefix`.
+///
+/// A previous install of the same component is removed first.
+pub fn install_component(dir: &Path, component: &str, prefix: &Path) -> Result<()> {
+    // The manifest records `prefix.join(path)`, which must not depend on
+    // the directory the install was run from.
+    let prefix = &std::path::absolute(prefix)?;
+    let source = dir.join(component);
+    let entries = read_lines(&source.join("manifest.in"))?;
+
+    // Check every entry before copying anything, so a bad archive leaves
+    // nothing behind.
+    let mut planned = Vec::new();
+    for entry in &entries {
+        let (kind, path) = entry.split_once(':').ok_or_else(|| {
+            Error::Install(format!(
+                "malformed manifest.in line in {}: {}",
+                component, entry
+            ))
+        })?;
+        if kind != "file" && kind != "dir" {
+            return Err(Error::Install(format!(
+                "unknown manifest.in entry in {}: {}",
+                component, entry
+            )));
+        }
+        let to = resolve_entry(prefix, path).ok_or_else(|| {
+            Error::Install(format!(
+                "manifest.in entry in {} is outside the prefix: {}",
+                component, entry
+            ))
+        })?;
+        planned.push((kind, source.join(path), to));
+    }
+
+    if installed_components(prefix)?.iter().any(|c| c == component) {
+        remove_component(prefix, component)?;
+    }
+
+    fs::create_dir_all(rustlib(prefix))?;
+
+    let mut installed = Vec::new();
+    for (kind, from, to) in planned {
+        if kind == "file" {
+            copy_file(&from, &to)?;
+        } else {
+            copy_dir(&from, &to)?;
+        }
+        installed.push(format!("{}:{}", kind, to.display()));
+    }
+
+    let mut contents = installed.join("\n");
+    contents.push('\n');
+    fs::write(manifest_file(prefix, component), contents)?;
+
+    let mut components = installed_components(prefix)?;
+    components.push(component.to_string());
+    write_installed_components(prefix, &components)?;
+    fs::write(
+        rustlib(prefix).join("rust-installer-version"),
+        format!("{}\n", INSTALLER_VERSION),
+    )?;
+
+    Ok(())
+}
+
+/// Remove the files recorded for `component` under `prefix`.
+///
+/// Relative entries are taken relative to `prefix`, and entries that don't
+/// resolve under it are left alone.
+pub fn remove_component(prefix: &Path, component: &str) -> Result<()> {
+    let prefix = &std::path::absolute(prefix)?;
+    let manifest = manifest_file(prefix, component);
+    for entry in read_lines(&manifest)? {
+        let Some((kind, path)) = entry.split_once(':') else {
+            continue;
+        };
+        let Some(path) = resolve_entry(prefix, path) else {
+            continue;
+        };
+        let result = match kind {
+            "file" => fs::remove_file(&path),
+            "dir" => fs::remove_dir_all(&path),
+            _ => continue,
+        };
+        match result {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        remove_empty_parents(&path, prefix);
+    }
+    fs::remove_file(&manifest)?;
+
+    let components: Vec<String> = installed_components(prefix)?
+        .into_iter()
+        .filter(|c| c != component)
+        .collect();
+    write_installed_components(prefix, &components)
+}
+
+/// The path a manifest entry refers to, if it's strictly inside `prefix`.
+fn resolve_entry(prefix: &Path, path: &str) -> Option<PathBuf> {
+    let path = prefix.join(path);
+    let inside = path.starts_with(prefix)
+        && path != prefix
+        && !path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir));
+    inside.then_some(path)
+}
+
+/// Rewrite the paths recorded under `prefix` after it was moved there from
+/// `from`, so the moved install can still be removed.
+pub fn relocate(prefix: &Path, from: &Path) -> Result<()> {
+    for component in installed_components(prefix)? {
+        let manifest = manifest_file(prefix, &component);
+        let mut contents = String::new();
+        for entry in read_lines(&manifest)? {
+            let Some((kind, path)) = entry.split_once(':') else {
+                continue;
+            };
+            let path = match Path::new(path).strip_prefix(from) {
+                Ok(relative) => prefix.join(relative),
+                Err(_) => PathBuf::from(path),
+            };
+            contents.push_str(&format!("{}:{}\n", kind, path.display()));
+        }
+        fs::write(&manifest, contents)?;
+    }
+    Ok(())
+}
+
+/// Remove every installed component from `prefix`, along with the
+/// installer's own files, returning the names of the components removed.
+pub fn remove_all(prefix: &Path) -> Result<Vec<String>> {
+    let prefix = &std::path::absolute(prefix)?;
+    let components = installed_components(prefix)?;
+    for component in &components {
+        remove_component(prefix, component)?;
+    }
+
+    // `uninstall.sh` is only there for installs made by `install.sh`.
+    for name in ["components", "rust-installer-version", "uninstall.sh"] {
+        let path = rustlib(prefix).join(name);
+        match fs::remove_file(&path) {
+            Ok(()) => remove_empty_parents(&path, prefix),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(components)
+}
+
+/// Remove directories left empty by an uninstall, stopping at `prefix`.
+fn remove_empty_parents(path: &Path, prefix: &Path) {
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        if d == prefix || !d.starts_with(prefix) || fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Replace rather than overwrite, a running binary can't be written to.
+    let _ = fs::remove_file(to);
+    fs::copy(from, to).map_err(|e| {
+        Error::Install(format!(
+            "copying {} to {}: {}",
+            from.display(),
+            to.display(),
+            e
+        ))
+    })?;
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            copy_file(&entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("get-rust-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// An unpacked package with a single `rustc` component.
+    fn package(dir: &Path) -> PathBuf {
+        let package = dir.join("rust-1.76.0-x86_64-unknown-linux-gnu");
+        let rustc = package.join("rustc");
+        fs::create_dir_all(rustc.join("bin")).unwrap();
+        fs::create_dir_all(rustc.join("share/doc/rust")).unwrap();
+        fs::write(package.join("rust-installer-version"), "3\n").unwrap();
+        fs::write(package.join("components"), "rustc\n").unwrap();
+        fs::write(
+            rustc.join("manifest.in"),
+            "file:bin/rustc\ndir:share/doc/rust\n",
+        )
+        .unwrap();
+        fs::write(rustc.join("bin/rustc"), "rustc").unwrap();
+        fs::write(rustc.join("share/doc/rust/README.md"), "docs").unwrap();
+        package
+    }
+
+    #[test]
+    fn install_and_remove() {
+        let dir = temp_dir("install-and-remove");
+        let package = package(&dir);
+        let prefix = dir.join("prefix");
+
+        install_component(&package, "rustc", &prefix).unwrap();
+        assert_eq!(package_components(&package).unwrap(), ["rustc"]);
+        assert_eq!(installed_components(&prefix).unwrap(), ["rustc"]);
+        assert!(prefix.join("bin/rustc").is_file());
+        assert!(prefix.join("share/doc/rust/README.md").is_file());
+        for entry in read_lines(&manifest_file(&prefix, "rustc")).unwrap() {
+            let (_, path) = entry.split_once(':').unwrap();
+            assert!(Path::new(path).starts_with(&prefix), "{}", entry);
+        }
+
+        assert_eq!(remove_all(&prefix).unwrap(), ["rustc"]);
+        assert!(installed_components(&prefix).unwrap().is_empty());
+        assert!(!prefix.join("bin").exists());
+        assert!(!prefix.join("share").exists());
+        assert!(!prefix.join("lib").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remove_only_inside_prefix() {
+        let dir = temp_dir("remove-only-inside-prefix");
+        let package = package(&dir);
+        let prefix = dir.join("prefix");
+        let outside = dir.join("outside");
+        fs::write(&outside, "keep").unwrap();
+
+        install_component(&package, "rustc", &prefix).unwrap();
+        let manifest = manifest_file(&prefix, "rustc");
+        fs::write(
+            &manifest,
+            format!(
+                "file:{}\nfile:../outside\nfile:bin/rustc\ndir:{}\n",
+                outside.display(),
+                prefix.display()
+            ),
+        )
+        .unwrap();
+
+        remove_component(&prefix, "rustc").unwrap();
+        assert!(outside.is_file());
+        assert!(!prefix.join("bin/rustc").exists());
+        assert!(prefix.join("share/doc/rust/README.md").is_file());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn install_only_inside_prefix() {
+        let dir = temp_dir("install-only-inside-prefix");
+        let package = package(&dir);
+        let prefix = dir.join("prefix");
+        let outside = dir.join("outside");
+        let manifest_in = package.join("rustc/manifest.in");
+
+        for entry in [
+            format!("file:{}", outside.display()),
+            "file:../outside".to_string(),
+            "file:bin/../../outside".to_string(),
+        ] {
+            fs::write(&manifest_in, format!("file:bin/rustc\n{}\n", entry)).unwrap();
+            assert!(
+                matches!(
+                    install_component(&package, "rustc", &prefix),
+                    Err(Error::Install(_))
+                ),
+                "{}",
+                entry
+            );
+            assert!(!outside.exists());
+            assert!(!prefix.join("bin/rustc").exists());
+            assert!(installed_components(&prefix).unwrap().is_empty());
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
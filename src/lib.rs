@@ -0,0 +1,23 @@
+pub mod channel;
+pub mod checksum;
+pub mod config;
+pub mod dist;
+pub mod download;
+pub mod elf;
+pub mod error;
+pub mod home;
+pub mod install;
+pub mod installer;
+pub mod list;
+pub mod overrides;
+pub mod progress;
+pub mod proxy;
+pub mod self_update;
+pub mod signature;
+pub mod targets;
+pub mod toolchain_file;
+pub mod triple;
+
+pub use error::{Error, Result};
+pub use install::install_rust;
+pub use triple::{InvalidTriple, TargetTriple};
@@ -0,0 +1,157 @@
+//! What's installed in the toolchain store and what the dist server offers.
+
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::channel::{self, Manifest, Source};
+use crate::error::Result;
+use crate::home;
+
+/// A toolchain in the store.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledToolchain {
+    pub name: String,
+    /// Release version, e.g. `1.76.0`, if the toolchain records one.
+    pub version: Option<String>,
+    /// Date of the release on the dist server.
+    pub release_date: Option<String>,
+    /// Date it was installed, `YYYY-MM-DD` in UTC.
+    pub installed: Option<String>,
+    /// Size on disk in bytes.
+    pub size: u64,
+    pub default: bool,
+}
+
+/// A release published on the dist server.
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailableRelease {
+    /// The name it's published under, e.g. `stable` or `1.75`.
+    pub name: String,
+    pub version: Option<String>,
+    pub date: String,
+    /// Targets with a standard library.
+    pub targets: Vec<String>,
+}
+
+pub fn installed_toolchains() -> Result<Vec<InstalledToolchain>> {
+    let default = home::default_toolchain();
+    let mut toolchains = Vec::new();
+
+    for name in home::installed_toolchains()? {
+        let dir = home::toolchain_dir(&name);
+        let manifest = Manifest::load_installed(&dir).ok();
+        let installed = fs::metadata(Manifest::installed_path(&dir))
+            .or_else(|_| fs::metadata(&dir))
+            .and_then(|m| m.modified())
+            .ok()
+            .map(format_date);
+
+        toolchains.push(InstalledToolchain {
+            version: manifest
+                .as_ref()
+                .and_then(|m| m.rust_version())
+                .map(|v| v.to_string()),
+            release_date: manifest.map(|m| m.date),
+            installed,
+            size: dir_size(&dir)?,
+            default: default.as_ref() == Some(&name),
+            name,
+        });
+    }
+    Ok(toolchains)
+}
+
+/// The current stable, beta and nightly releases, then the newest release of
+/// each of the `minors` stable minor versions before the current one.
+pub async fn available_releases(source: Source<'_>, minors: u64) -> Result<Vec<AvailableRelease>> {
+    let mut releases = Vec::new();
+    let mut latest = None;
+
+    for name in ["stable", "beta", "nightly"] {
+        if let Some(manifest) = channel::fetch_manifest(name, None, source).await? {
+            if name == "stable" {
+                latest = manifest.rust_version();
+            }
+            releases.push(release(name, &manifest));
+        }
+    }
+
+    if let Some(latest) = latest {
+        let oldest = latest.minor.saturating_sub(minors);
+        for minor in (oldest..latest.minor).rev() {
+            let name = format!("{}.{}", latest.major, minor);
+            if let Some(manifest) = channel::fetch_manifest(&name, None, source).await? {
+                releases.push(release(&name, &manifest));
+            }
+        }
+    }
+
+    Ok(releases)
+}
+
+fn release(name: &str, manifest: &Manifest) -> AvailableRelease {
+    AvailableRelease {
+        name: name.to_string(),
+        version: manifest.rust_version().map(|v| v.to_string()),
+        date: manifest.date.clone(),
+        targets: manifest.targets(),
+    }
+}
+
+/// Total size of the files under `path`, not following symlinks.
+fn dir_size(path: &Path) -> Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        size += dir_size(&entry?.path())?;
+    }
+    Ok(size)
+}
+
+/// A size in bytes as e.g. `312.4 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// `time` as a `YYYY-MM-DD` date in UTC.
+fn format_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    // Days since 1970-01-01 to a civil date, after Howard Hinnant's
+    // `civil_from_days`.
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -1,266 +1,514 @@
 use std::{
-    borrow::Borrow,
-    fs::File,
-    io::{Read, Write},
+    collections::BTreeMap,
+    path::{Path, PathBuf},
 };
 
-use indicatif::{ProgressBar, ProgressStyle};
+use clap::{Parser, Subcommand};
+
+use get_rust::{
+    channel::Source,
+    config::Config,
+    home,
+    install::{self, InstallOptions},
+    installer, list,
+    overrides::Overrides,
+    proxy, self_update,
+    signature::Keyring,
+    toolchain_file::ToolchainFile,
+    Error, TargetTriple,
+};
 
-use flate2;
-use reqwest;
-use tar;
-use tokio;
+/// Toolchain installed when neither `--version` nor a toolchain file is
+/// given.
+const DEFAULT_VERSION: &str = "stable";
+
+#[derive(Parser)]
+#[command(name = "get-rust", about = "Download and install Rust toolchains")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Root of the dist server to download from [env: GET_RUST_DIST_SERVER,
+    /// RUSTUP_DIST_SERVER]
+    #[arg(long, global = true)]
+    dist_server: Option<String>,
+    /// Mirror to fall back to when the dist server fails, may be repeated
+    #[arg(long = "mirror", value_name = "URL", global = true)]
+    mirrors: Vec<String>,
+    /// Never touch the network, only read from file:// dist servers and
+    /// local archives
+    #[arg(long, global = true)]
+    offline: bool,
+    /// Also trust signatures made by this public key file, may be repeated
+    #[arg(long = "trusted-key", value_name = "FILE", global = true)]
+    trusted_keys: Vec<PathBuf>,
+}
 
-use core::time::Duration;
+#[derive(Subcommand)]
+enum Command {
+    /// Download and install a toolchain, by default the one pinned by the
+    /// project's `rust-toolchain.toml`
+    Install {
+        /// Channel or version to install, e.g. `stable`, `nightly-2024-02-01`,
+        /// `1.76`, `1.76.0` or `>=1.70`
+        #[arg(long)]
+        version: Option<String>,
+        /// Target triple or alias to install for, e.g. `linux-x64`, `alpine` or
+        /// `rpi4`, defaults to the host
+        #[arg(long)]
+        target: Option<String>,
+        /// Install into this directory instead of the toolchain store
+        #[arg(long)]
+        prefix: Option<PathBuf>,
+        /// Only install these components, e.g. `rustc,cargo,rust-std`
+        #[arg(long, visible_alias = "component", value_delimiter = ',')]
+        components: Vec<String>,
+        /// Leave these components out
+        #[arg(long, value_delimiter = ',')]
+        without: Vec<String>,
+        /// Component profile to install, e.g. `minimal`, `default` or
+        /// `complete`
+        #[arg(long, conflicts_with = "archive")]
+        profile: Option<String>,
+        /// Install from a local `rust-*.tar.gz` instead of the dist server,
+        /// its `.sha256` and `.asc` files must sit next to it
+        #[arg(long, value_name = "FILE", conflicts_with = "version")]
+        archive: Option<PathBuf>,
+    },
+    /// Remove an installed toolchain
+    Uninstall {
+        /// Toolchain to remove, e.g. `1.70.0` or `nightly-x86_64-unknown-linux-gnu`
+        #[arg(required_unless_present = "prefix")]
+        toolchain: Option<String>,
+        /// Remove the toolchain installed in this directory instead
+        #[arg(long, conflicts_with = "toolchain")]
+        prefix: Option<PathBuf>,
+        /// Run the `uninstall.sh` left by an old `install.sh` based install
+        #[arg(long, requires = "prefix", conflicts_with = "toolchain")]
+        legacy: bool,
+    },
+    /// List installed toolchains, or the components of one
+    List {
+        /// List the components installed in this directory instead
+        #[arg(long)]
+        prefix: Option<PathBuf>,
+        /// List releases on the dist server and the targets they support
+        #[arg(long, conflicts_with = "prefix")]
+        available: bool,
+        /// How many stable minor versions before the current one to list
+        #[arg(long, default_value_t = 5, requires = "available")]
+        releases: u64,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show or set the default toolchain
+    Default {
+        /// Installed toolchain to make the default, e.g. `stable` or
+        /// `1.70.0-x86_64-unknown-linux-gnu`
+        toolchain: Option<String>,
+    },
+    /// Show the detected host and current settings
+    Show {
+        /// Compare the detected host with this toolchain's rustc instead of
+        /// the one on PATH
+        #[arg(long)]
+        toolchain: Option<String>,
+    },
+    /// Manage the targets an installed toolchain can build for
+    Target {
+        #[command(subcommand)]
+        command: TargetCommand,
+    },
+    /// Install the latest release of every stable, beta and nightly toolchain
+    Update {
+        /// Remove the releases being replaced instead of keeping them
+        #[arg(long)]
+        prune: bool,
+    },
+    /// Manage get-rust itself
+    #[command(name = "self")]
+    Self_ {
+        #[command(subcommand)]
+        command: SelfCommand,
+    },
+    /// Pin a toolchain for a directory and everything below it
+    Override {
+        #[command(subcommand)]
+        command: OverrideCommand,
+    },
+}
 
-static LIST_ARCHS: &[&str] = &[
-    "i386",
-    "i586",
-    "i686",
-    "x86_64",
-    "arm",
-    "armv7",
-    "armv7s",
-    "aarch64",
-    "mips",
-    "mipsel",
-    "mips64",
-    "mips64el",
-    "powerpc",
-    "powerpc64",
-    "powerpc64le",
-    "riscv64gc",
-    "s390x",
-    "loongarch64",
-];
-static LIST_OSES: &[&str] = &[
-    "pc-windows",
-    "unknown-linux",
-    "apple-darwin",
-    "unknown-netbsd",
-    "apple-ios",
-    "linux",
-    "rumprun-netbsd",
-    "unknown-freebsd",
-    "unknown-illumos",
-];
-static LIST_ENVS: &[&str] = &[
-    "gnu",
-    "gnux32",
-    "msvc",
-    "gnueabi",
-    "gnueabihf",
-    "gnuabi64",
-    "androideabi",
-    "android",
-    "musl",
-];
+#[derive(Subcommand)]
+enum TargetCommand {
+    /// Install the standard library for more targets
+    Add {
+        /// Target triples or aliases to add, e.g. `aarch64-unknown-linux-gnu`
+        /// or `wasm`
+        #[arg(required = true)]
+        targets: Vec<String>,
+        /// Toolchain to add them to, defaults to the default toolchain
+        #[arg(long)]
+        toolchain: Option<String>,
+        /// Add them to the toolchain installed in this directory instead
+        #[arg(long, conflicts_with = "toolchain")]
+        prefix: Option<PathBuf>,
+    },
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct TargetTriple {
-    pub arch: Option<String>,
-    pub os: Option<String>,
-    pub env: Option<String>,
+#[derive(Subcommand)]
+enum SelfCommand {
+    /// Replace get-rust with the latest verified release
+    Update,
 }
 
-impl TargetTriple {
-    pub fn str(&self) -> String {
-        let mut triple = String::new();
-        if let Some(arch) = &self.arch {
-            triple.push_str(arch);
-        }
-        if let Some(os) = &self.os {
-            triple.push_str("-");
-            triple.push_str(os);
-        }
-        if let Some(env) = &self.env {
-            triple.push_str("-");
-            triple.push_str(env);
-        }
-        triple
-    }
+#[derive(Subcommand)]
+enum OverrideCommand {
+    /// Use a toolchain in a directory
+    Set {
+        /// Installed toolchain to use
+        toolchain: String,
+        /// Directory to set it for, defaults to the current one
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Stop overriding the toolchain in a directory
+    Unset {
+        /// Directory to unset it for, defaults to the current one
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// List directory overrides
+    List,
+}
 
-    pub fn new(arch: Option<String>, os: Option<String>, env: Option<String>) -> Self {
-        TargetTriple { arch, os, env }
+/// The `--target` triple or alias, or the host. It's checked against the
+/// manifest of the release being installed, or against the targets rustc
+/// knows when there is none.
+fn target_or_host(target: Option<String>, aliases: &BTreeMap<String, String>) -> TargetTriple {
+    match target {
+        Some(target) => TargetTriple::resolve_alias(&target, aliases),
+        None => TargetTriple::get_with_no_rust_installed(),
     }
+}
+
+/// The store directory of `toolchain`, or of the default toolchain.
+fn toolchain_prefix(toolchain: Option<String>) -> get_rust::Result<PathBuf> {
+    let name = match toolchain {
+        Some(name) => home::require_toolchain(&name)?,
+        None => home::default_toolchain().ok_or_else(|| {
+            Error::Toolchain("no default toolchain, run `get-rust default <toolchain>`".to_string())
+        })?,
+    };
+    Ok(home::toolchain_dir(&name))
+}
 
-    pub fn from_target_triple(triple: &str) -> Self {
-        let mut parts = triple.split('-');
-        let arch = parts.next().map(|s| s.to_string());
-        let os = parts.next().map(|s| s.to_string());
-        let env = parts.next().map(|s| s.to_string());
-        TargetTriple { arch, os, env }
+fn print_json<T: serde::Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("error: {}", e),
     }
+}
 
-    pub fn to_target_triple(&self) -> String {
-        let mut triple = String::new();
-        if let Some(arch) = &self.arch {
-            triple.push_str(arch);
+/// `path` or the current directory, made absolute.
+fn override_dir(path: Option<PathBuf>) -> get_rust::Result<PathBuf> {
+    let path = match path {
+        Some(path) => path,
+        None => std::env::current_dir()?,
+    };
+    Ok(path.canonicalize()?)
+}
+
+async fn run(cli: Cli) -> get_rust::Result<()> {
+    let config = Config::load()?;
+    let mut dist = config.dist_servers(cli.dist_server, cli.mirrors);
+    dist.set_offline(cli.offline || config.offline);
+    let trusted_keys: Vec<PathBuf> = config
+        .trusted_keys
+        .iter()
+        .cloned()
+        .chain(cli.trusted_keys)
+        .collect();
+
+    match cli.command {
+        Command::Install {
+            version,
+            target,
+            prefix,
+            components,
+            without,
+            profile,
+            archive,
+        } => {
+            let triple = target_or_host(target, &config.aliases);
+            println!("Target triple: {}", triple);
+
+            let file = match (&version, &archive) {
+                (None, None) => ToolchainFile::find(&std::env::current_dir()?)?,
+                _ => None,
+            };
+            let mut options = match file {
+                Some(file) => {
+                    println!("Using toolchain from {}", file.path.display());
+                    let mut options = InstallOptions::new(triple, file.channel);
+                    options.components = file.components;
+                    options.targets = file
+                        .targets
+                        .iter()
+                        .map(|t| TargetTriple::from_target_triple(t))
+                        .collect();
+                    // Components listed in the file come on top of the profile.
+                    options.profile = file.profile.or_else(|| Some("default".to_string()));
+                    options
+                }
+                None => {
+                    let version = version.unwrap_or_else(|| DEFAULT_VERSION.to_string());
+                    InstallOptions::new(triple, version)
+                }
+            };
+            // Recorded paths are joined onto the prefix, so it has to be
+            // absolute to mean the same thing from another directory.
+            options.prefix = prefix.map(std::path::absolute).transpose()?;
+            options.components.extend(components);
+            if profile.is_some() {
+                options.profile = profile;
+            }
+            options.without = without;
+            options.trusted_keys = trusted_keys;
+            options.dist = dist;
+            options.archive = archive;
+
+            get_rust::install_rust(&options).await
         }
-        if let Some(os) = &self.os {
-            triple.push_str("-");
-            triple.push_str(os);
+        Command::Uninstall {
+            toolchain,
+            prefix,
+            legacy,
+        } => match (toolchain, prefix) {
+            (_, Some(prefix)) if legacy => install::uninstall_legacy(&prefix).await,
+            (_, Some(prefix)) => install::uninstall_rust(&prefix, None),
+            (Some(toolchain), None) => install::uninstall_toolchain(&toolchain),
+            (None, None) => unreachable!("clap requires a toolchain or --prefix"),
+        },
+        Command::List {
+            prefix: Some(prefix),
+            json,
+            ..
+        } => {
+            let components = installer::installed_components(&prefix)?;
+            if json {
+                print_json(&components);
+            } else {
+                for component in components {
+                    println!("{}", component);
+                }
+            }
+            Ok(())
         }
-        if let Some(env) = &self.env {
-            triple.push_str("-");
-            triple.push_str(env);
+        Command::List {
+            available: true,
+            releases,
+            json,
+            ..
+        } => {
+            let keyring = Keyring::with_trusted_keys(&trusted_keys)?;
+            let source = Source {
+                dist: &dist,
+                keyring: &keyring,
+            };
+            let releases = list::available_releases(source, releases).await?;
+            if json {
+                print_json(&releases);
+            } else {
+                for release in releases {
+                    println!(
+                        "{} {} ({})",
+                        release.name,
+                        release.version.as_deref().unwrap_or("unknown"),
+                        release.date
+                    );
+                    println!("    {}", release.targets.join(" "));
+                }
+            }
+            Ok(())
         }
-        triple
-    }
+        Command::List { json, .. } => {
+            let toolchains = list::installed_toolchains()?;
+            if json {
+                print_json(&toolchains);
+                return Ok(());
+            }
 
-    pub fn is_valid(&self) -> bool {
-        if let Some(arch) = &self.arch {
-            if !LIST_ARCHS.contains(&arch.as_str()) {
-                return false;
+            let width = toolchains.iter().map(|t| t.name.len()).max().unwrap_or(0);
+            for toolchain in toolchains {
+                println!(
+                    "{:<width$}  {:<14}  {:<10}  {:>10}{}",
+                    toolchain.name,
+                    toolchain.version.as_deref().unwrap_or("-"),
+                    toolchain.installed.as_deref().unwrap_or("-"),
+                    list::format_size(toolchain.size),
+                    if toolchain.default { "  (default)" } else { "" },
+                    width = width
+                );
             }
+            Ok(())
         }
-        if let Some(os) = &self.os {
-            if !LIST_OSES.contains(&os.as_str()) {
-                return false;
+        Command::Default { toolchain } => {
+            match toolchain {
+                Some(toolchain) => {
+                    home::set_default_toolchain(&toolchain)?;
+                    println!(
+                        "Default toolchain set to {}",
+                        home::require_toolchain(&toolchain)?
+                    );
+                }
+                None => match home::default_toolchain() {
+                    Some(name) => println!("{}", name),
+                    None => println!("no default toolchain"),
+                },
             }
+            Ok(())
+        }
+        Command::Target {
+            command:
+                TargetCommand::Add {
+                    targets,
+                    toolchain,
+                    prefix,
+                },
+        } => {
+            let mut options =
+                InstallOptions::new(TargetTriple::get_with_no_rust_installed(), String::new());
+            options.prefix = Some(match prefix {
+                Some(prefix) => std::path::absolute(prefix)?,
+                None => toolchain_prefix(toolchain)?,
+            });
+            options.trusted_keys = trusted_keys;
+            options.dist = dist;
+
+            let targets: Vec<TargetTriple> = targets
+                .iter()
+                .map(|t| TargetTriple::resolve_alias(t, &config.aliases))
+                .collect();
+            install::add_targets(&options, &targets).await
+        }
+        Command::Update { prune } => {
+            let mut options =
+                InstallOptions::new(TargetTriple::get_with_no_rust_installed(), String::new());
+            options.trusted_keys = trusted_keys;
+            options.dist = dist;
+            install::update_toolchains(&options, prune).await
+        }
+        Command::Self_ {
+            command: SelfCommand::Update,
+        } => {
+            let keyring = Keyring::with_trusted_keys(&trusted_keys)?;
+            self_update::self_update(&config.update_root()?, &dist, &keyring).await
         }
-        if let Some(env) = &self.env {
-            if !LIST_ENVS.contains(&env.as_str()) {
-                return false;
+        Command::Override {
+            command: OverrideCommand::Set { toolchain, path },
+        } => {
+            let toolchain = home::require_toolchain(&toolchain)?;
+            let dir = override_dir(path)?;
+            let mut overrides = Overrides::load()?;
+            overrides.set(&dir, &toolchain);
+            overrides.save()?;
+            println!("Override set to {} for {}", toolchain, dir.display());
+            Ok(())
+        }
+        Command::Override {
+            command: OverrideCommand::Unset { path },
+        } => {
+            let dir = override_dir(path)?;
+            let mut overrides = Overrides::load()?;
+            if overrides.unset(&dir) {
+                overrides.save()?;
+                println!("Override removed for {}", dir.display());
+            } else {
+                println!("No override set for {}", dir.display());
             }
+            Ok(())
         }
-        true
-    }
-
-    pub fn get_with_no_rust_installed() -> Self {
-        let arch = std::env::consts::ARCH.to_string();
-        let os = std::env::consts::OS.to_string();
+        Command::Override {
+            command: OverrideCommand::List,
+        } => {
+            for (dir, toolchain) in &Overrides::load()?.overrides {
+                println!("{}\t{}", dir, toolchain);
+            }
+            Ok(())
+        }
+        Command::Show { toolchain } => {
+            let host = TargetTriple::get_with_no_rust_installed();
+            println!("Host triple: {}", host);
 
-        let os_matching = match os.as_str() {
-            "linux" => "unknown-linux",
-            "macos" => "apple-darwin",
-            "windows" => "pc-windows",
-            "netbsd" => {
-                if cfg!(target_os = "rumprun") {
-                    "rumprun-netbsd"
-                } else {
-                    "unknown-netbsd"
+            let rustc = match &toolchain {
+                Some(name) => {
+                    home::toolchain_dir(&home::require_toolchain(name)?).join("bin/rustc")
+                }
+                None => PathBuf::from("rustc"),
+            };
+            match TargetTriple::from_rustc(&rustc) {
+                Ok(rustc_host) => {
+                    println!("rustc host: {} ({})", rustc_host, rustc.display());
+                    if rustc_host != host {
+                        println!(
+                            "warning: {} was built for {}, but the host looks like {}",
+                            rustc.display(),
+                            rustc_host,
+                            host
+                        );
+                    }
                 }
+                Err(e) if toolchain.is_some() => return Err(e),
+                Err(_) => println!("rustc host: no rustc found"),
             }
-            "ios" => "apple-ios",
-            "freebsd" => "unknown-freebsd",
-            "illumos" => "unknown-illumos",
-
-            _ => "unknown",
-        };
-
-        let env = match os.as_str() {
-            "windows" => "msvc",
-            "linux" => match arch.as_str() {
-                "x86_64" => "gnu",
-                "x86" => "gnu",
-                "aarch64" => "gnu",
-                "arm" => "gnueabi",
-                "armv7" => "gnueabihf",
-                "armv7s" => "gnueabihf",
-                "mips" => "gnu",
-                "mipsel" => "gnu",
-                "mips64" => "gnuabi64",
-                "mips64el" => "gnuabi64",
-                "powerpc" => "gnu",
-                "powerpc64" => "gnu",
-                "powerpc64le" => "gnu",
-                "riscv64gc" => "gnu",
-                "s390x" => "gnu",
-                "loongarch64" => "gnu",
-                _ => "gnu",
-            },
-            "solaris" => "gnu",
-            "macos" => "gnu",
-            "netbsd" => "gnu",
-            "ios" => "gnu",
-            "freebsd" => "gnu",
-            "illumos" => "gnu",
-            _ => "failed",
-        };
 
-        TargetTriple::new(
-            Some(arch),
-            Some(os_matching.to_string()),
-            Some(env.to_string()),
-        )
+            println!(
+                "Default toolchain: {}",
+                home::default_toolchain().unwrap_or_else(|| "none".to_string())
+            );
+            let cwd = std::env::current_dir()?;
+            if let Some((dir, toolchain)) = Overrides::load()?.find(&cwd) {
+                println!("Override: {} (set for {})", toolchain, dir.display());
+            }
+            if let Some(file) = ToolchainFile::find(&cwd)? {
+                println!("Toolchain file: {} ({})", file.channel, file.path.display());
+            }
+            println!("get-rust home: {}", home::get_rust_home().display());
+            println!("Dist server: {}", dist.primary());
+            for mirror in &dist.servers()[1..] {
+                println!("Mirror: {}", mirror);
+            }
+            if dist.offline() {
+                println!("Offline mode: on");
+            }
+            Ok(())
+        }
     }
 }
 
-async fn install_rust(triple: TargetTriple, version: String) {
-    let target = triple.str();
-    println!("Installing Rust for target: {}", target);
-
-    let download_url = format!(
-        "https://static.rust-lang.org/dist/rust-{}-{}.tar.gz",
-        version, target
-    );
-
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
-            .template("{spinner:.green} {msg}").unwrap(),
-    );
-
-    pb.set_message("Downloading...");
-
-    pb.enable_steady_tick(Duration::from_millis(100));
-
-    // Download the file
-    let response = reqwest::get(&download_url).await;
-
-    if response.is_err() {
-        pb.set_message("Failed to download");
-        pb.finish();
-        return;
-    }
-
-    pb.set_message("Unwrapping...");
-
-    // save the file
-    let file = response.unwrap().bytes().await.unwrap();
-
-    let tar = flate2::read::GzDecoder::new(&file[..]);
-
-    pb.set_message("Extracting...");
-
-
-    let mut archive = tar::Archive::new(tar);
-    // save files
-
-    pb.set_message("Unpacking...");
-
-    archive
-        .unpack(format!("rust-{}-{}", version, triple.str()))
-        .unwrap();
-
-
-    pb.set_message("Running install.sh...");
-
-    // run install.sh
-
-    let command_res =
-        tokio::process::Command::new(format!("rust-{}-{}/rust-{0}-{1}/install.sh", version, triple.str()))
-            .spawn();
-
-    if command_res.is_err() {
-        pb.set_message("Failed to run install.sh");
-        pb.finish();
-        return;
-    } else {
-        pb.set_message("Done");
-        pb.finish();
-    
+/// Run as a proxy if invoked as `rustc`, `cargo` and so on.
+fn run_proxy() -> Option<i32> {
+    let mut args = std::env::args_os();
+    let tool = proxy::proxy_name(Path::new(&args.next()?))?;
+    match proxy::run(tool, args.collect()) {
+        Ok(code) => Some(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            Some(1)
+        }
     }
 }
 
 #[tokio::main]
 async fn main() {
-    let target = TargetTriple::get_with_no_rust_installed();
-    println!("Target triple: {}", target.str());
+    if let Some(code) = run_proxy() {
+        std::process::exit(code);
+    }
 
-    let version = "1.76.0".to_string();
+    let cli = Cli::parse();
 
-    install_rust(target, version).await;
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
 }
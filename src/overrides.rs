@@ -0,0 +1,66 @@
+//! Per-directory toolchain overrides, kept in `$GET_RUST_HOME/overrides.toml`:
+//!
+//! ```toml
+//! [overrides]
+//! "/home/me/legacy-service" = "1.70.0-x86_64-unknown-linux-gnu"
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::home;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Overrides {
+    #[serde(default)]
+    pub overrides: BTreeMap<String, String>,
+}
+
+impl Overrides {
+    pub fn path() -> PathBuf {
+        home::get_rust_home().join("overrides.toml")
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Overrides::path();
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| Error::Config(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Overrides::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::create_dir_all(home::get_rust_home())?;
+        let contents = toml::to_string(self).map_err(|e| Error::Config(e.to_string()))?;
+        fs::write(Overrides::path(), contents)?;
+        Ok(())
+    }
+
+    pub fn set(&mut self, dir: &Path, toolchain: &str) {
+        self.overrides
+            .insert(dir.display().to_string(), toolchain.to_string());
+    }
+
+    /// Remove the override for `dir`, returning whether there was one.
+    pub fn unset(&mut self, dir: &Path) -> bool {
+        self.overrides.remove(&dir.display().to_string()).is_some()
+    }
+
+    /// The override for `dir` or its closest ancestor that has one, along
+    /// with the directory it was set on.
+    pub fn find(&self, dir: &Path) -> Option<(&Path, &str)> {
+        dir.ancestors().find_map(|ancestor| {
+            self.overrides
+                .get_key_value(&ancestor.display().to_string())
+                .map(|(path, toolchain)| (Path::new(path.as_str()), toolchain.as_str()))
+        })
+    }
+}
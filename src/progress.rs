@@ -0,0 +1,62 @@
+use core::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Spinner used for phases with no measurable progress.
+pub fn spinner() -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+/// Byte-counting bar for a download.
+///
+/// Starts out as a spinner showing bytes and speed; call
+/// [`ProgressBar::set_length`] once `Content-Length` is known to turn it into
+/// a bar with an ETA.
+pub fn download_bar(name: &str) -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
+            .template("{spinner:.green} {msg} {bytes} ({bytes_per_sec})")
+            .unwrap(),
+    );
+    pb.set_message(name.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
+/// Switch a [`download_bar`] to a full bar once the total size is known.
+pub fn set_download_length(pb: &ProgressBar, len: u64) {
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    pb.set_length(len);
+}
+
+/// Bar for unpacking an archive of `len` compressed bytes, with the number of
+/// entries unpacked so far in its prefix.
+pub fn extract_bar(name: &str, len: u64) -> ProgressBar {
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "{msg} [{bar:40.green/white}] {bytes}/{total_bytes}, {prefix} entries ({eta})",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    pb.set_message(name.to_string());
+    pb.set_prefix("0");
+    pb
+}
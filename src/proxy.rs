@@ -0,0 +1,165 @@
+//! `rustc`, `cargo` and friends as proxies for the selected toolchain.
+//!
+//! get-rust is a multi-call binary: hard links to it named after a tool live
+//! in `$GET_RUST_HOME/bin`, and when invoked under one of those names it runs
+//! that tool from the toolchain chosen by, in order:
+//!
+//! 1. a leading `+toolchain` argument, e.g. `cargo +nightly build`
+//! 2. the `GET_RUST_TOOLCHAIN` environment variable
+//! 3. a directory override, see [`crate::overrides`]
+//! 4. a `rust-toolchain.toml` or `rust-toolchain` file, see
+//!    [`crate::toolchain_file`]
+//! 5. the default toolchain
+
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::error::{Error, Result};
+use crate::home;
+use crate::overrides::Overrides;
+use crate::toolchain_file::ToolchainFile;
+
+/// Tools a proxy is installed for.
+pub static PROXIES: &[&str] = &[
+    "rustc",
+    "rustdoc",
+    "cargo",
+    "rustfmt",
+    "cargo-fmt",
+    "clippy-driver",
+    "cargo-clippy",
+];
+
+/// Set for the proxied tool so that tools it runs in turn (cargo running
+/// rustc) stay on the same toolchain.
+const TOOLCHAIN_ENV: &str = "GET_RUST_TOOLCHAIN";
+
+/// The tool `argv0` names, if get-rust was invoked as a proxy.
+pub fn proxy_name(argv0: &Path) -> Option<&'static str> {
+    let stem = argv0.file_stem()?.to_str()?;
+    PROXIES.iter().copied().find(|name| *name == stem)
+}
+
+/// Where proxies are installed, add this to `PATH`.
+pub fn bin_dir() -> PathBuf {
+    home::get_rust_home().join("bin")
+}
+
+/// Link every proxy in [`bin_dir`] to the running executable.
+pub fn install_proxies() -> Result<()> {
+    link_proxies(&env::current_exe()?)
+}
+
+/// Link every proxy in [`bin_dir`] to `exe`.
+///
+/// Hard links are used so the proxies keep working if the get-rust binary
+/// is replaced, falling back to copies across filesystems.
+pub fn link_proxies(exe: &Path) -> Result<()> {
+    let bin = bin_dir();
+    fs::create_dir_all(&bin)?;
+
+    for name in PROXIES {
+        let proxy = bin.join(format!("{}{}", name, env::consts::EXE_SUFFIX));
+        if proxy == *exe {
+            continue;
+        }
+        let _ = fs::remove_file(&proxy);
+        if fs::hard_link(exe, &proxy).is_err() {
+            fs::copy(exe, &proxy)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether [`bin_dir`] is on `PATH`.
+pub fn bin_dir_on_path() -> bool {
+    let bin = bin_dir();
+    env::var_os("PATH")
+        .map(|path| env::split_paths(&path).any(|p| p == bin))
+        .unwrap_or(false)
+}
+
+/// Pick the toolchain for a proxied invocation, removing a leading
+/// `+toolchain` from `args`.
+pub fn select_toolchain(args: &mut Vec<OsString>) -> Result<String> {
+    if let Some(name) = args
+        .first()
+        .and_then(|a| a.to_str())
+        .and_then(|a| a.strip_prefix('+'))
+    {
+        let name = home::require_toolchain(name)?;
+        args.remove(0);
+        return Ok(name);
+    }
+
+    if let Some(name) = env::var(TOOLCHAIN_ENV).ok().filter(|n| !n.is_empty()) {
+        return home::require_toolchain(&name);
+    }
+
+    let cwd = env::current_dir()?;
+    if let Some((dir, name)) = Overrides::load()?.find(&cwd) {
+        return home::resolve_toolchain(name).ok_or_else(|| {
+            Error::Toolchain(format!(
+                "toolchain `{}` set for {} is not installed",
+                name,
+                dir.display()
+            ))
+        });
+    }
+
+    if let Some(file) = ToolchainFile::find(&cwd)? {
+        return home::resolve_toolchain(&file.channel).ok_or_else(|| {
+            Error::Toolchain(format!(
+                "toolchain `{}` from {} is not installed, run `get-rust install` to install it",
+                file.channel,
+                file.path.display()
+            ))
+        });
+    }
+
+    home::default_toolchain().ok_or_else(|| {
+        Error::Toolchain(
+            "no default toolchain, run `get-rust default <toolchain>` or pass `+toolchain`"
+                .to_string(),
+        )
+    })
+}
+
+/// Run `tool` from the selected toolchain with `args`, returning its exit
+/// code. On Unix the proxy process is replaced and this only returns on error.
+pub fn run(tool: &str, mut args: Vec<OsString>) -> Result<i32> {
+    let toolchain = select_toolchain(&mut args)?;
+    let binary = home::toolchain_dir(&toolchain).join("bin").join(format!(
+        "{}{}",
+        tool,
+        env::consts::EXE_SUFFIX
+    ));
+    if !binary.is_file() {
+        return Err(Error::Toolchain(format!(
+            "`{}` is not installed in toolchain {}",
+            tool, toolchain
+        )));
+    }
+
+    let mut command = Command::new(&binary);
+    command.args(args).env(TOOLCHAIN_ENV, &toolchain);
+    exec(command)
+}
+
+#[cfg(unix)]
+fn exec(mut command: Command) -> Result<i32> {
+    use std::os::unix::process::CommandExt;
+
+    Err(command.exec().into())
+}
+
+#[cfg(not(unix))]
+fn exec(mut command: Command) -> Result<i32> {
+    let status = command.status()?;
+    Ok(status.code().unwrap_or(1))
+}
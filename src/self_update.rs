@@ -0,0 +1,89 @@
+//! Replacing the running get-rust binary with a newer release.
+//!
+//! Releases are looked up under the configured update root as
+//! `<root>/<host triple>/get-rust[.exe]`, with `.sha256` and `.asc` files
+//! next to the binary like on the dist server.
+
+use std::{env, fs, path::Path};
+
+use crate::checksum;
+use crate::dist::DistServers;
+use crate::download;
+use crate::error::{Error, Result};
+use crate::progress;
+use crate::proxy;
+use crate::signature::{self, Keyring};
+use crate::triple::TargetTriple;
+
+/// Download the release for this host from `root`, verify it and replace
+/// the running binary with it. `dist` decides whether the network may be
+/// used.
+pub async fn self_update(root: &str, dist: &DistServers, keyring: &Keyring) -> Result<()> {
+    let exe = env::current_exe()?;
+    let host = TargetTriple::get_with_no_rust_installed().to_string();
+    let file_name = format!("get-rust{}", env::consts::EXE_SUFFIX);
+
+    let mut server = DistServers::new(root.to_string(), Vec::new());
+    server.set_offline(dist.offline());
+    let urls = vec![format!("{}/{}/{}", server.primary(), host, file_name)];
+
+    // Download next to the binary so the final rename stays on one filesystem.
+    let dir = exe.parent().unwrap_or(Path::new("."));
+    let new = dir.join(format!(".{}.new", file_name));
+
+    let bar = progress::download_bar(&file_name);
+    let digest = match download::download_file(&server, &urls, &new, &bar).await {
+        Ok(digest) => {
+            bar.finish();
+            digest
+        }
+        Err(e) => {
+            bar.abandon_with_message("Failed to download");
+            return Err(e);
+        }
+    };
+
+    let pb = progress::spinner();
+    pb.set_message("Verifying...");
+    let verified = async {
+        let expected = checksum::fetch_sha256(&server, &urls).await?;
+        checksum::verify_digest(&file_name, &digest, &expected)?;
+        let asc = signature::fetch_signature(&server, &urls).await?;
+        keyring.verify_file(&new, &asc)
+    };
+    if let Err(e) = verified.await {
+        let _ = fs::remove_file(&new);
+        pb.abandon_with_message("Verification failed");
+        return Err(e);
+    }
+    pb.finish_and_clear();
+
+    if checksum::sha256_file(&exe)? == digest {
+        fs::remove_file(&new)?;
+        println!("get-rust is up to date");
+        return Ok(());
+    }
+
+    replace(&new, &exe)?;
+    // The proxies are hard links to the old binary.
+    proxy::link_proxies(&exe)?;
+    println!("Updated {}", exe.display());
+    Ok(())
+}
+
+#[cfg(unix)]
+fn replace(new: &Path, exe: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(new, fs::Permissions::from_mode(0o755))?;
+    fs::rename(new, exe).map_err(|e| Error::Install(format!("replacing {}: {}", exe.display(), e)))
+}
+
+/// A running executable can't be replaced on Windows, but it can be renamed.
+#[cfg(windows)]
+fn replace(new: &Path, exe: &Path) -> Result<()> {
+    let old = exe.with_extension("old.exe");
+    let _ = fs::remove_file(&old);
+    fs::rename(exe, &old)?;
+    fs::rename(new, exe).map_err(|e| Error::Install(format!("replacing {}: {}", exe.display(), e)))
+}
@@ -0,0 +1,198 @@
+use std::{fs, io::Read, path::Path};
+
+use pgp::composed::{Deserializable, DetachedSignature, SignedPublicKey};
+
+use crate::dist::DistServers;
+use crate::error::{Error, Result};
+use crate::home;
+
+/// The Rust release signing key, fingerprint
+/// `108F 6620 5EAE B0AA A8DD 5E1C 85AB 96E6 FA1B E5FE`.
+static RUST_KEY: &str = include_str!("rust-key.gpg.ascii");
+
+/// Public keys that are trusted to sign manifests and archives.
+#[derive(Debug, Clone)]
+pub struct Keyring {
+    keys: Vec<SignedPublicKey>,
+}
+
+impl Keyring {
+    /// A keyring holding only the embedded Rust release key.
+    pub fn rust() -> Self {
+        let (key, _) = SignedPublicKey::from_string(RUST_KEY).expect("embedded Rust key is valid");
+        Keyring { keys: vec![key] }
+    }
+
+    /// The Rust release key plus every `*.asc` key in `$GET_RUST_HOME/keys`
+    /// and the given extra key files.
+    pub fn with_trusted_keys(extra: &[impl AsRef<Path>]) -> Result<Self> {
+        let mut keyring = Keyring::rust();
+
+        if let Ok(entries) = fs::read_dir(home::get_rust_home().join("keys")) {
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "asc"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                keyring.add_file(&path)?;
+            }
+        }
+
+        for path in extra {
+            keyring.add_file(path.as_ref())?;
+        }
+
+        Ok(keyring)
+    }
+
+    pub fn add_armored(&mut self, armored: &str) -> Result<()> {
+        let (key, _) = SignedPublicKey::from_string(armored)
+            .map_err(|e| Error::Signature(format!("invalid public key: {}", e)))?;
+        self.keys.push(key);
+        Ok(())
+    }
+
+    pub fn add_file(&mut self, path: &Path) -> Result<()> {
+        let armored = fs::read_to_string(path)?;
+        self.add_armored(&armored)
+            .map_err(|e| Error::Signature(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Check an ASCII-armored detached signature over `data`.
+    ///
+    /// Release signatures are made by a signing subkey, so subkeys are tried
+    /// as well as primary keys.
+    pub fn verify(&self, name: &str, data: &[u8], armored_signature: &str) -> Result<()> {
+        self.verify_with(name, armored_signature, || Ok(data))
+    }
+
+    /// Like [`Keyring::verify`], but reads the signed data from a file so it
+    /// never has to be held in memory.
+    pub fn verify_file(&self, path: &Path, armored_signature: &str) -> Result<()> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        self.verify_with(&name, armored_signature, || {
+            Ok(std::io::BufReader::new(fs::File::open(path)?))
+        })
+    }
+
+    fn verify_with<R: Read>(
+        &self,
+        name: &str,
+        armored_signature: &str,
+        mut open: impl FnMut() -> Result<R>,
+    ) -> Result<()> {
+        let (signature, _) = DetachedSignature::from_string(armored_signature)
+            .map_err(|e| Error::Signature(format!("invalid signature for {}: {}", name, e)))?;
+
+        for key in &self.keys {
+            if signature
+                .signature
+                .verify(&key.primary_key, open()?)
+                .is_ok()
+            {
+                return Ok(());
+            }
+            for subkey in &key.public_subkeys {
+                if signature.signature.verify(&subkey.key, open()?).is_ok() {
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(Error::Signature(format!(
+            "{} is not signed by a trusted key",
+            name
+        )))
+    }
+}
+
+/// Fetch the `.asc` signature published next to a file, trying each of its
+/// `urls` in turn.
+pub async fn fetch_signature(dist: &DistServers, urls: &[String]) -> Result<String> {
+    let asc: Vec<String> = urls.iter().map(|url| format!("{}.asc", url)).collect();
+    dist.fetch_text(&asc).await?.ok_or_else(|| {
+        Error::Signature(format!(
+            "no signature published for {}",
+            urls.first().map(String::as_str).unwrap_or_default()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An Ed25519 key made for these tests with
+    /// `gpg --quick-gen-key "get-rust test <test@example.com>" ed25519 sign`.
+    const TEST_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEatKjPBYJKwYBBAHaRw8BAQdAKmq0xe5xFAOg+0hpuHxYPAO+f6LvYCwW5UHN
+MLrdv7W0IGdldC1ydXN0IHRlc3QgPHRlc3RAZXhhbXBsZS5jb20+iJAEExYIADgW
+IQRawav3PaigF/khFsOzgYYztgxy8gUCatKjPAIbAwULCQgHAgYVCgkICwIEFgID
+AQIeAQIXgAAKCRCzgYYztgxy8kE1AP40ErsgVTKk+oSOQN5UiKjxuDytN00A5h9F
+u3rdeACWKQD+PQPCD9vI2SFHsds7FBsVlxTbFM8gpt2ecvHjnGVNEAg=
+=3wS8
+-----END PGP PUBLIC KEY BLOCK-----
+";
+
+    const DATA: &[u8] = b"rust-1.76.0-x86_64-unknown-linux-gnu.tar.gz";
+
+    /// `DATA` signed by `TEST_KEY`.
+    const SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIcEABYIAC8WIQRawav3PaigF/khFsOzgYYztgxy8gUCatKjPBEcdGVzdEBleGFt
+cGxlLmNvbQAKCRCzgYYztgxy8v53AQCsdQ9ZeCysfsZISfWJPaZfOouqCLlWQWx/
+E8SI5CgMbgEAiO+bMfTMHp5m6lR1pVBlEFRuVpPzjzvH1ql5tBW3UAo=
+=eYB3
+-----END PGP SIGNATURE-----
+";
+
+    /// `DATA` signed by another key, not in any keyring.
+    const UNTRUSTED_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIwEABYIADQWIQRgwZWFfX7zT9VVbn11cWjGqFrY8QUCatKjPBYcdW50cnVzdGVk
+QGV4YW1wbGUuY29tAAoJEHVxaMaoWtjxUG0BALXHpgH0Lnzq76P1SfmuYXATkN3Y
+ALQjuH6cY/pxGkX8AQCaRHRIT8gGh/4fy8RpqRDaHQLf5EC+0OZad4weadygCQ==
+=E9C8
+-----END PGP SIGNATURE-----
+";
+
+    fn keyring() -> Keyring {
+        let mut keyring = Keyring::rust();
+        keyring.add_armored(TEST_KEY).unwrap();
+        keyring
+    }
+
+    #[test]
+    fn trusted_signature() {
+        keyring().verify("data", DATA, SIGNATURE).unwrap();
+    }
+
+    #[test]
+    fn untrusted_signature() {
+        let err = keyring()
+            .verify("data", DATA, UNTRUSTED_SIGNATURE)
+            .unwrap_err();
+        assert!(err.to_string().contains("not signed by a trusted key"));
+        assert!(Keyring::rust().verify("data", DATA, SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn tampered_data() {
+        let tampered = b"rust-1.76.1-x86_64-unknown-linux-gnu.tar.gz";
+        assert!(keyring().verify("data", tampered, SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(keyring().verify("data", DATA, "not a signature").is_err());
+        assert!(Keyring::rust().add_armored("not a key").is_err());
+    }
+}
@@ -0,0 +1,1627 @@
+//! Every target rustc knows about, with its support tier.
+//!
+//! Generated by `cargo run --example gen_targets` from
+//! rustc 1.95.0 (59807616e 2026-04-14), do not edit.
+
+/// A target built into rustc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    pub triple: &'static str,
+    /// Support tier, 1 to 3. Targets the platform support page doesn't
+    /// list are counted as tier 3.
+    pub tier: u8,
+    /// Whether rustc and cargo are published for it.
+    pub host_tools: bool,
+}
+
+/// Sorted by triple.
+pub static TARGETS: &[Target] = &[
+    Target {
+        triple: "aarch64-apple-darwin",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-apple-ios",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-ios-macabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-ios-sim",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-tvos",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-tvos-sim",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-visionos",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-visionos-sim",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-watchos",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-apple-watchos-sim",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-kmc-solid_asp3",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-linux-android",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-nintendo-switch-freestanding",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-pc-windows-gnullvm",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-pc-windows-msvc",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-freebsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-fuchsia",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-helenos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-hermit",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-illumos",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-linux-gnu",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-linux-gnu_ilp32",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-linux-musl",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-linux-ohos",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-managarm-mlibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-none",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-none-softfloat",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-nto-qnx700",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-nto-qnx710",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-nto-qnx710_iosock",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-nto-qnx800",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-nuttx",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64-unknown-redox",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-teeos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-trusty",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-unknown-uefi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-uwp-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64_be-unknown-hermit",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64_be-unknown-linux-gnu",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64_be-unknown-linux-gnu_ilp32",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64_be-unknown-linux-musl",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64_be-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "aarch64_be-unknown-none-softfloat",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64v8r-unknown-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "aarch64v8r-unknown-none-softfloat",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "amdgcn-amd-amdhsa",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm-linux-androideabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm-unknown-linux-gnueabi",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "arm-unknown-linux-gnueabihf",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "arm-unknown-linux-musleabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm-unknown-linux-musleabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm64_32-apple-watchos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm64e-apple-darwin",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "arm64e-apple-ios",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm64e-apple-tvos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "arm64ec-pc-windows-msvc",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armeb-unknown-linux-gnueabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armebv7r-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armebv7r-none-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv4t-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv4t-unknown-linux-gnueabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv5te-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv5te-unknown-linux-gnueabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv5te-unknown-linux-musleabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv5te-unknown-linux-uclibceabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv6-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv6-none-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv6-unknown-freebsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv6-unknown-netbsd-eabihf",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv6k-nintendo-3ds",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-linux-androideabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-rtems-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-sony-vita-newlibeabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-unknown-freebsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv7-unknown-linux-gnueabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-unknown-linux-gnueabihf",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv7-unknown-linux-musleabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-unknown-linux-musleabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-unknown-linux-ohos",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv7-unknown-linux-uclibceabi",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv7-unknown-linux-uclibceabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-unknown-netbsd-eabihf",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "armv7-unknown-trusty",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7-wrs-vxworks-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-kmc-solid_asp3-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-kmc-solid_asp3-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-none-eabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-nuttx-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7a-vex-v5",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7k-apple-watchos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7r-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7r-none-eabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv7s-apple-ios",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "armv8r-none-eabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "avr-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "bpfeb-unknown-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "bpfel-unknown-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "csky-unknown-linux-gnuabiv2",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "csky-unknown-linux-gnuabiv2hf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "hexagon-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "hexagon-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "hexagon-unknown-qurt",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i386-apple-ios",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i586-unknown-linux-gnu",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i586-unknown-linux-musl",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i586-unknown-netbsd",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i586-unknown-redox",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-apple-darwin",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-linux-android",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-pc-nto-qnx700",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-pc-windows-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-pc-windows-gnullvm",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-pc-windows-msvc",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-freebsd",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-unknown-haiku",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-helenos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-unknown-hurd-gnu",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-linux-gnu",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-linux-musl",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "i686-unknown-uefi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-uwp-windows-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-uwp-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-win7-windows-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-win7-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "i686-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "loongarch32-unknown-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "loongarch32-unknown-none-softfloat",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "loongarch64-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "loongarch64-unknown-linux-musl",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "loongarch64-unknown-linux-ohos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "loongarch64-unknown-none",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "loongarch64-unknown-none-softfloat",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "m68k-unknown-linux-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "m68k-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mips-mti-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mips-unknown-linux-gnu",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mips-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mips-unknown-linux-uclibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mips64-openwrt-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mips64-unknown-linux-gnuabi64",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mips64-unknown-linux-muslabi64",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mips64el-unknown-linux-gnuabi64",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mips64el-unknown-linux-muslabi64",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-mti-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-sony-psp",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-sony-psx",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-unknown-linux-gnu",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mipsel-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-unknown-linux-uclibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsel-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "mipsel-unknown-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsisa32r6-unknown-linux-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsisa32r6el-unknown-linux-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsisa64r6-unknown-linux-gnuabi64",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "mipsisa64r6el-unknown-linux-gnuabi64",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "msp430-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "nvptx64-nvidia-cuda",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-freebsd",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-helenos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc-unknown-linux-gnuspe",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-linux-muslspe",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc-unknown-openbsd",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc-wrs-vxworks-spe",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc64-ibm-aix",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc64-unknown-freebsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64-unknown-linux-musl",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "powerpc64le-unknown-freebsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64le-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "powerpc64le-unknown-linux-musl",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "riscv32-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32e-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32em-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32emc-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32gc-unknown-linux-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32gc-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32i-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32im-risc0-zkvm-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32im-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32ima-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imac-esp-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imac-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imac-unknown-nuttx-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imac-unknown-xous-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imafc-esp-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imafc-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imafc-unknown-nuttx-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imc-esp-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imc-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv32imc-unknown-nuttx-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64-linux-android",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64a23-unknown-linux-gnu",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-freebsd",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-fuchsia",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-hermit",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "riscv64gc-unknown-linux-musl",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-managarm-mlibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "riscv64gc-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-nuttx-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64gc-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "riscv64gc-unknown-redox",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64im-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64imac-unknown-none-elf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "riscv64imac-unknown-nuttx-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "s390x-unknown-linux-gnu",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "s390x-unknown-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "s390x-unknown-none-softfloat",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "sparc-unknown-linux-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "sparc-unknown-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "sparc64-unknown-helenos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "sparc64-unknown-linux-gnu",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "sparc64-unknown-netbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "sparc64-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "sparcv9-sun-solaris",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "thumbv4t-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv5te-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv6-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv6m-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv6m-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-none-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-nuttx-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-pc-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7a-uwp-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7em-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7em-none-eabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7em-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7em-nuttx-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7m-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7m-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7neon-linux-androideabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7neon-unknown-linux-gnueabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7neon-unknown-linux-musleabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7r-none-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv7r-none-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.base-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.base-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.main-none-eabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.main-none-eabihf",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.main-nuttx-eabi",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8m.main-nuttx-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "thumbv8r-none-eabihf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-unknown-emscripten",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-unknown-unknown",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-wali-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-wasip1",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-wasip1-threads",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-wasip2",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32-wasip3",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm32v1-none",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "wasm64-unknown-unknown",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-apple-darwin",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-apple-ios",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-apple-ios-macabi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-apple-tvos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-apple-watchos-sim",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-fortanix-unknown-sgx",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-linux-android",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-lynx-lynxos178",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-pc-cygwin",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-pc-nto-qnx710",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-pc-nto-qnx710_iosock",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-pc-nto-qnx800",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-pc-solaris",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-pc-windows-gnu",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-pc-windows-gnullvm",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-pc-windows-msvc",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unikraft-linux-musl",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-dragonfly",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-freebsd",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-fuchsia",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-haiku",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-helenos",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-hermit",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-hurd-gnu",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-illumos",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-l4re-uclibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-gnu",
+        tier: 1,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-gnuasan",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-gnux32",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-musl",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-none",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-linux-ohos",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-managarm-mlibc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-motor",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-netbsd",
+        tier: 2,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-none",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-openbsd",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-unknown-redox",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-trusty",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-unknown-uefi",
+        tier: 2,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-uwp-windows-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-uwp-windows-msvc",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-win7-windows-gnu",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64-win7-windows-msvc",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "x86_64-wrs-vxworks",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "x86_64h-apple-darwin",
+        tier: 3,
+        host_tools: true,
+    },
+    Target {
+        triple: "xtensa-esp32-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "xtensa-esp32-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "xtensa-esp32s2-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "xtensa-esp32s2-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "xtensa-esp32s3-espidf",
+        tier: 3,
+        host_tools: false,
+    },
+    Target {
+        triple: "xtensa-esp32s3-none-elf",
+        tier: 3,
+        host_tools: false,
+    },
+];
+
+/// The target named `triple`, if rustc has it.
+pub fn find(triple: &str) -> Option<&'static Target> {
+    TARGETS
+        .binary_search_by(|t| t.triple.cmp(triple))
+        .ok()
+        .map(|i| &TARGETS[i])
+}
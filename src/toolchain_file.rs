@@ -0,0 +1,141 @@
+//! Project toolchain pins: `rust-toolchain.toml`, or the legacy
+//! `rust-toolchain` file holding either the same TOML or just a channel name.
+//!
+//! ```toml
+//! [toolchain]
+//! channel = "1.76"
+//! components = ["rustfmt", "clippy"]
+//! targets = ["wasm32-unknown-unknown"]
+//! profile = "minimal"
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+/// Names looked for in each directory, in order of preference.
+const FILE_NAMES: &[&str] = &["rust-toolchain.toml", "rust-toolchain"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainFile {
+    /// The file this was read from.
+    pub path: PathBuf,
+    pub channel: String,
+    pub components: Vec<String>,
+    pub targets: Vec<String>,
+    pub profile: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Contents {
+    toolchain: Toolchain,
+}
+
+#[derive(Deserialize)]
+struct Toolchain {
+    channel: Option<String>,
+    #[serde(default)]
+    components: Vec<String>,
+    #[serde(default)]
+    targets: Vec<String>,
+    profile: Option<String>,
+}
+
+impl ToolchainFile {
+    /// The toolchain file in `dir` or the closest ancestor that has one.
+    pub fn find(dir: &Path) -> Result<Option<Self>> {
+        for ancestor in dir.ancestors() {
+            for name in FILE_NAMES {
+                let path = ancestor.join(name);
+                if path.is_file() {
+                    let contents = fs::read_to_string(&path)?;
+                    return ToolchainFile::parse(&path, &contents).map(Some);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse(path: &Path, contents: &str) -> Result<Self> {
+        let invalid =
+            |message: String| Error::Toolchain(format!("invalid {}: {}", path.display(), message));
+
+        let legacy = path.file_name().is_some_and(|n| n == "rust-toolchain");
+        let trimmed = contents.trim();
+        if legacy && !trimmed.is_empty() && !trimmed.contains(['\n', '[', '=']) {
+            return Ok(ToolchainFile {
+                path: path.to_path_buf(),
+                channel: trimmed.to_string(),
+                components: Vec::new(),
+                targets: Vec::new(),
+                profile: None,
+            });
+        }
+
+        let contents: Contents = toml::from_str(contents).map_err(|e| invalid(e.to_string()))?;
+        let toolchain = contents.toolchain;
+        let channel = toolchain
+            .channel
+            .ok_or_else(|| invalid("no `channel` in [toolchain]".to_string()))?;
+        Ok(ToolchainFile {
+            path: path.to_path_buf(),
+            channel,
+            components: toolchain.components,
+            targets: toolchain.targets,
+            profile: toolchain.profile,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml() {
+        let path = Path::new("rust-toolchain.toml");
+        let file = ToolchainFile::parse(
+            path,
+            r#"
+            [toolchain]
+            channel = "1.76"
+            components = ["rustfmt", "clippy"]
+            targets = ["wasm32-unknown-unknown"]
+            profile = "minimal"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file.path, path);
+        assert_eq!(file.channel, "1.76");
+        assert_eq!(file.components, ["rustfmt", "clippy"]);
+        assert_eq!(file.targets, ["wasm32-unknown-unknown"]);
+        assert_eq!(file.profile.as_deref(), Some("minimal"));
+    }
+
+    #[test]
+    fn legacy() {
+        let file =
+            ToolchainFile::parse(Path::new("rust-toolchain"), "nightly-2024-02-01\n").unwrap();
+        assert_eq!(file.channel, "nightly-2024-02-01");
+        assert!(file.components.is_empty());
+
+        let file = ToolchainFile::parse(
+            Path::new("rust-toolchain"),
+            "[toolchain]\nchannel = \"stable\"\n",
+        )
+        .unwrap();
+        assert_eq!(file.channel, "stable");
+    }
+
+    #[test]
+    fn invalid() {
+        for contents in ["", "[toolchain]\nprofile = \"minimal\"\n", "stable"] {
+            assert!(ToolchainFile::parse(Path::new("rust-toolchain.toml"), contents).is_err());
+        }
+    }
+}
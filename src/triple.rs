@@ -0,0 +1,670 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path, process::Command, str::FromStr};
+
+use crate::elf::{self, Elf};
+use crate::error::{Error, Result};
+use crate::targets::{self, Target};
+
+/// Vendors that can appear in a triple. The second part of a three-part
+/// triple is the vendor when it's listed here (`x86_64-unknown-uefi`) and
+/// the OS otherwise (`aarch64-linux-android`, `thumbv7em-none-eabihf`).
+static LIST_VENDORS: &[&str] = &[
+    "amd", "apple", "esp", "esp32", "esp32s2", "esp32s3", "fortanix", "ibm", "kmc", "lynx", "mti",
+    "nintendo", "nvidia", "openwrt", "pc", "risc0", "rumprun", "sony", "sun", "unikraft",
+    "unknown", "uwp", "vex", "wali", "win7", "wrs",
+];
+
+/// Friendlier names for common targets, accepted wherever a triple is.
+static ALIASES: &[(&str, &str)] = &[
+    ("linux-x64", "x86_64-unknown-linux-gnu"),
+    ("linux-arm64", "aarch64-unknown-linux-gnu"),
+    ("linux-x86", "i686-unknown-linux-gnu"),
+    ("alpine", "x86_64-unknown-linux-musl"),
+    ("alpine-arm64", "aarch64-unknown-linux-musl"),
+    ("x64-mac", "x86_64-apple-darwin"),
+    ("arm64-mac", "aarch64-apple-darwin"),
+    ("win64", "x86_64-pc-windows-msvc"),
+    ("win32", "i686-pc-windows-msvc"),
+    ("win-arm64", "aarch64-pc-windows-msvc"),
+    ("mingw64", "x86_64-pc-windows-gnu"),
+    ("rpi", "arm-unknown-linux-gnueabihf"),
+    ("rpi3", "armv7-unknown-linux-gnueabihf"),
+    ("rpi4", "aarch64-unknown-linux-gnu"),
+    ("rpi5", "aarch64-unknown-linux-gnu"),
+    ("wasm", "wasm32-unknown-unknown"),
+    ("wasi", "wasm32-wasip1"),
+];
+
+/// How many suggestions an [`InvalidTriple`] offers at most.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A triple that isn't one of rustc's targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTriple {
+    pub triple: String,
+    /// Known targets close to `triple`, closest first.
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for InvalidTriple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a known target triple", self.triple)?;
+        let quoted: Vec<String> = self
+            .suggestions
+            .iter()
+            .map(|s| format!("`{}`", s))
+            .collect();
+        match quoted.split_last() {
+            None => Ok(()),
+            Some((last, [])) => write!(f, ", did you mean {}?", last),
+            Some((last, rest)) => write!(f, ", did you mean {} or {}?", rest.join(", "), last),
+        }
+    }
+}
+
+impl std::error::Error for InvalidTriple {}
+
+/// Known targets within a few edits of `triple`, closest first.
+fn suggestions(triple: &str) -> Vec<String> {
+    closest(triple, targets::TARGETS.iter().map(|t| t.triple))
+}
+
+/// The `candidates` within a few edits of `name`, closest first.
+fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<String> {
+    // Allow roughly one typo per part of a triple.
+    let max_distance = (name.len() / 6).max(2);
+
+    let mut close: Vec<(usize, &str)> = candidates
+        .map(|c| (strsim::levenshtein(name, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort();
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, t)| t.to_string())
+        .collect()
+}
+
+/// A target triple, `<arch>[-<vendor>]-<os>[-<env>]`, e.g.
+/// `x86_64-unknown-linux-gnu`, `aarch64-apple-darwin`, `aarch64-linux-android`
+/// or `wasm32-wasip1`. `env` holds the ABI too, as in `thumbv7em-none-eabihf`.
+///
+/// It parses with [`FromStr`], which only accepts targets rustc knows, and
+/// prints with [`Display`](fmt::Display). With the `serde` feature it
+/// serializes as the triple string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TargetTriple {
+    pub arch: Option<String>,
+    pub vendor: Option<String>,
+    pub os: Option<String>,
+    pub env: Option<String>,
+}
+
+impl TargetTriple {
+    #[deprecated(note = "use `to_string()`")]
+    pub fn str(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn new(
+        arch: Option<String>,
+        vendor: Option<String>,
+        os: Option<String>,
+        env: Option<String>,
+    ) -> Self {
+        TargetTriple {
+            arch,
+            vendor,
+            os,
+            env,
+        }
+    }
+
+    pub fn from_target_triple(triple: &str) -> Self {
+        let parts: Vec<&str> = triple.split('-').collect();
+        let arch = parts.first().map(|s| s.to_string());
+        let rest = parts.get(1..).unwrap_or_default();
+
+        // Four parts always have a vendor, three only when it's a known one.
+        let has_vendor = rest.len() >= 3 || (rest.len() == 2 && LIST_VENDORS.contains(&rest[0]));
+        let (vendor, rest) = match rest.split_first() {
+            Some((vendor, rest)) if has_vendor => (Some(vendor.to_string()), rest),
+            _ => (None, rest),
+        };
+        let os = rest.first().map(|s| s.to_string());
+        let env = match rest.get(1..) {
+            Some(env) if !env.is_empty() => Some(env.join("-")),
+            _ => None,
+        };
+
+        TargetTriple {
+            arch,
+            vendor,
+            os,
+            env,
+        }
+    }
+
+    #[deprecated(note = "use `to_string()`")]
+    pub fn to_target_triple(&self) -> String {
+        self.to_string()
+    }
+
+    /// The triple a built-in alias like `linux-x64`, `arm64-mac` or `rpi4`
+    /// stands for.
+    pub fn from_alias(alias: &str) -> Option<Self> {
+        ALIASES
+            .iter()
+            .find(|(name, _)| *name == alias)
+            .map(|(_, triple)| TargetTriple::from_target_triple(triple))
+    }
+
+    /// Parse `name` as one of `aliases`, then as a built-in alias, then as a
+    /// triple. User aliases can override built-in ones and must name a known
+    /// target.
+    pub fn parse_with_aliases(
+        name: &str,
+        aliases: &BTreeMap<String, String>,
+    ) -> std::result::Result<Self, InvalidTriple> {
+        if let Some(triple) = aliases.get(name) {
+            return triple.parse();
+        }
+        match TargetTriple::from_alias(name) {
+            Some(triple) => Ok(triple),
+            None => name.parse().map_err(|e: InvalidTriple| {
+                let names = aliases
+                    .keys()
+                    .map(String::as_str)
+                    .chain(ALIASES.iter().map(|(alias, _)| *alias))
+                    .chain(targets::TARGETS.iter().map(|t| t.triple));
+                InvalidTriple {
+                    suggestions: closest(name, names),
+                    ..e
+                }
+            }),
+        }
+    }
+
+    /// Look `name` up like [`parse_with_aliases`](Self::parse_with_aliases)
+    /// does, but without checking it against the targets rustc knows today.
+    /// For triples that are checked against a release's manifest instead,
+    /// which can have targets since renamed, like `wasm32-wasi`.
+    pub fn resolve_alias(name: &str, aliases: &BTreeMap<String, String>) -> Self {
+        match aliases.get(name) {
+            Some(triple) => TargetTriple::from_target_triple(triple),
+            None => TargetTriple::from_alias(name)
+                .unwrap_or_else(|| TargetTriple::from_target_triple(name)),
+        }
+    }
+
+    /// Check that this is a target rustc knows, suggesting close matches
+    /// if it isn't.
+    pub fn validate(&self) -> std::result::Result<&'static Target, InvalidTriple> {
+        let triple = self.to_string();
+        targets::find(&triple).ok_or_else(|| InvalidTriple {
+            suggestions: suggestions(&triple),
+            triple,
+        })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// The rustc target this triple names, with its tier and whether host
+    /// tools are available.
+    pub fn target(&self) -> Option<&'static Target> {
+        targets::find(&self.to_string())
+    }
+
+    /// The host an existing `rustc` was built for, from the `host:` line of
+    /// `rustc -vV`. A bare `rustc` is looked up on `PATH`.
+    pub fn from_rustc(rustc: &Path) -> Result<Self> {
+        let output = Command::new(rustc)
+            .arg("-vV")
+            .output()
+            .map_err(|e| Error::Toolchain(format!("failed to run {}: {}", rustc.display(), e)))?;
+        if !output.status.success() {
+            return Err(Error::Toolchain(format!(
+                "{} -vV exited with {}",
+                rustc.display(),
+                output.status
+            )));
+        }
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("host:"))
+            .map(|host| TargetTriple::from_target_triple(host.trim()))
+            .ok_or_else(|| Error::Toolchain(format!("{} -vV printed no host", rustc.display())))
+    }
+
+    /// Detect the host from the running system.
+    ///
+    /// On Linux this looks at the userland's binaries, so musl and 32-bit
+    /// userlands and hard-float ARM are told apart from what get-rust itself
+    /// was built for. Elsewhere, and if that fails, it goes by the platform
+    /// get-rust was built for.
+    pub fn get_with_no_rust_installed() -> Self {
+        if cfg!(target_os = "linux") {
+            if let Some(triple) = detect_linux() {
+                return triple;
+            }
+        }
+
+        let arch = match std::env::consts::ARCH {
+            "x86" => "i686",
+            "riscv64" => "riscv64gc",
+            arch => arch,
+        }
+        .to_string();
+        let os = std::env::consts::OS.to_string();
+
+        let (vendor, os_matching) = match os.as_str() {
+            "linux" => ("unknown", "linux"),
+            "macos" => ("apple", "darwin"),
+            "windows" => ("pc", "windows"),
+            "netbsd" => ("unknown", "netbsd"),
+            "ios" => ("apple", "ios"),
+            "freebsd" => ("unknown", "freebsd"),
+            "illumos" => ("unknown", "illumos"),
+            "solaris" => ("pc", "solaris"),
+            os => ("unknown", os),
+        };
+
+        let env = match os.as_str() {
+            "windows" => Some("msvc"),
+            "linux" => Some(match arch.as_str() {
+                "arm" => "gnueabi",
+                "armv7" => "gnueabihf",
+                "mips64" => "gnuabi64",
+                "mips64el" => "gnuabi64",
+                _ => "gnu",
+            }),
+            // Darwin, the BSDs, illumos and Solaris triples have no env.
+            _ => None,
+        };
+
+        TargetTriple::new(
+            Some(arch),
+            Some(vendor.to_string()),
+            Some(os_matching.to_string()),
+            env.map(str::to_string),
+        )
+    }
+}
+
+impl fmt::Display for TargetTriple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<&str> = [&self.arch, &self.vendor, &self.os, &self.env]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        f.write_str(&parts.join("-"))
+    }
+}
+
+impl FromStr for TargetTriple {
+    type Err = InvalidTriple;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let triple = TargetTriple::from_target_triple(s);
+        triple.validate()?;
+        Ok(triple)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TargetTriple {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TargetTriple {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let triple = String::deserialize(deserializer)?;
+        triple.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The Linux host triple going by `/bin/sh`, or get-rust's own binary if
+/// that isn't ELF, and `/proc/cpuinfo` for ARM.
+fn detect_linux() -> Option<TargetTriple> {
+    let sh = Elf::read(Path::new("/bin/sh"));
+    let exe = Elf::read(Path::new("/proc/self/exe"));
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    linux_triple(sh.as_ref(), exe.as_ref(), &cpuinfo)
+}
+
+/// The Linux triple for a userland with shell `sh` and a dynamically linked
+/// `exe`, see [`detect_linux`].
+fn linux_triple(sh: Option<&Elf>, exe: Option<&Elf>, cpuinfo: &str) -> Option<TargetTriple> {
+    let userland = sh.or(exe)?;
+
+    // A static shell (busybox) doesn't name the libc, a dynamic get-rust does.
+    let interpreter = [sh, exe]
+        .into_iter()
+        .flatten()
+        .find_map(|e| e.interpreter.clone())
+        .unwrap_or_default();
+    let libc = if interpreter.contains("ld-musl") {
+        "musl"
+    } else {
+        "gnu"
+    };
+    let hard_float =
+        interpreter.contains("armhf") || userland.flags & elf::EF_ARM_ABI_FLOAT_HARD != 0;
+
+    let (arch, abi) = match userland.machine {
+        elf::EM_X86_64 if userland.is_64 => ("x86_64", ""),
+        elf::EM_X86_64 => ("x86_64", "x32"),
+        elf::EM_386 => ("i686", ""),
+        elf::EM_AARCH64 => ("aarch64", ""),
+        elf::EM_ARM if hard_float => (arm_arch(cpuinfo), "eabihf"),
+        elf::EM_ARM => (arm_arch(cpuinfo), "eabi"),
+        elf::EM_RISCV if userland.is_64 => ("riscv64gc", ""),
+        elf::EM_PPC64 if userland.little_endian => ("powerpc64le", ""),
+        elf::EM_PPC64 => ("powerpc64", ""),
+        elf::EM_PPC => ("powerpc", ""),
+        elf::EM_S390 => ("s390x", ""),
+        elf::EM_MIPS => match (userland.is_64, userland.little_endian) {
+            (true, true) => ("mips64el", "abi64"),
+            (true, false) => ("mips64", "abi64"),
+            (false, true) => ("mipsel", ""),
+            (false, false) => ("mips", ""),
+        },
+        elf::EM_LOONGARCH => ("loongarch64", ""),
+        elf::EM_SPARCV9 => ("sparc64", ""),
+        _ => return None,
+    };
+
+    let triple = TargetTriple::new(
+        Some(arch.to_string()),
+        Some("unknown".to_string()),
+        Some("linux".to_string()),
+        Some(format!("{}{}", libc, abi)),
+    );
+    triple.is_valid().then_some(triple)
+}
+
+/// `armv7` on ARMv7 and later CPUs with NEON, which includes 64-bit CPUs
+/// running a 32-bit userland, `arm` otherwise, going by `/proc/cpuinfo`.
+fn arm_arch(cpuinfo: &str) -> &'static str {
+    let field = |name: &str| {
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == name).then(|| value.trim().to_string())
+        })
+    };
+
+    let version: u32 = field("CPU architecture")
+        .and_then(|v| {
+            v.trim_start_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .ok()
+        })
+        .unwrap_or(0);
+    let features = field("Features").unwrap_or_default();
+    let neon = features
+        .split_whitespace()
+        .any(|f| f == "neon" || f == "asimd");
+
+    if version >= 8 || (version == 7 && neon) {
+        "armv7"
+    } else {
+        "arm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(arch: &str, vendor: Option<&str>, os: &str, env: Option<&str>) -> TargetTriple {
+        TargetTriple::new(
+            Some(arch.to_string()),
+            vendor.map(str::to_string),
+            Some(os.to_string()),
+            env.map(str::to_string),
+        )
+    }
+
+    fn elf(machine: u16, is_64: bool, flags: u32, interpreter: Option<&str>) -> Elf {
+        Elf {
+            is_64,
+            little_endian: true,
+            machine,
+            flags,
+            interpreter: interpreter.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn linux_userlands() {
+        let glibc = elf(elf::EM_X86_64, true, 0, Some("/lib64/ld-linux-x86-64.so.2"));
+        let musl = elf(elf::EM_X86_64, true, 0, Some("/lib/ld-musl-x86_64.so.1"));
+        let busybox = elf(elf::EM_X86_64, true, 0, None);
+        let x32 = elf(elf::EM_X86_64, false, 0, Some("/libx32/ld-linux-x32.so.2"));
+        let i686 = elf(elf::EM_386, false, 0, Some("/lib/ld-linux.so.2"));
+        let armhf = elf(
+            elf::EM_ARM,
+            false,
+            elf::EF_ARM_ABI_FLOAT_HARD,
+            Some("/lib/ld-linux-armhf.so.3"),
+        );
+        let armel = elf(elf::EM_ARM, false, 0, Some("/lib/ld-linux.so.3"));
+        let cpuinfo = "CPU architecture: 7\nFeatures\t: half thumb vfp neon\n";
+
+        let cases = [
+            (Some(&glibc), None, "x86_64-unknown-linux-gnu"),
+            (Some(&musl), None, "x86_64-unknown-linux-musl"),
+            // A static busybox, with get-rust itself naming the libc.
+            (Some(&busybox), Some(&musl), "x86_64-unknown-linux-musl"),
+            (None, Some(&glibc), "x86_64-unknown-linux-gnu"),
+            (Some(&x32), Some(&glibc), "x86_64-unknown-linux-gnux32"),
+            (Some(&i686), Some(&glibc), "i686-unknown-linux-gnu"),
+            (Some(&armhf), None, "armv7-unknown-linux-gnueabihf"),
+            (Some(&armel), None, "armv7-unknown-linux-gnueabi"),
+        ];
+        for (sh, exe, expected) in cases {
+            let triple = linux_triple(sh, exe, cpuinfo).unwrap();
+            assert_eq!(triple.to_string(), expected);
+        }
+        assert_eq!(linux_triple(None, None, cpuinfo), None);
+    }
+
+    #[test]
+    fn arm_arches() {
+        assert_eq!(
+            arm_arch("CPU architecture: 7\nFeatures\t: vfp neon\n"),
+            "armv7"
+        );
+        assert_eq!(arm_arch("CPU architecture: 7\nFeatures\t: vfp\n"), "arm");
+        assert_eq!(
+            arm_arch("CPU architecture: 8\nFeatures\t: fp asimd\n"),
+            "armv7"
+        );
+        assert_eq!(arm_arch("CPU architecture: 5TEJ\n"), "arm");
+        assert_eq!(arm_arch(""), "arm");
+    }
+
+    #[test]
+    fn round_trips_every_target() {
+        for target in targets::TARGETS {
+            assert_eq!(
+                TargetTriple::from_target_triple(target.triple).to_string(),
+                target.triple
+            );
+        }
+    }
+
+    #[test]
+    fn from_str_round_trips() {
+        for s in [
+            "x86_64-unknown-linux-gnu",
+            "aarch64-linux-android",
+            "wasm32-unknown-unknown",
+            "x86_64-fortanix-unknown-sgx",
+        ] {
+            let triple: TargetTriple = s.parse().unwrap();
+            assert_eq!(triple, TargetTriple::from_target_triple(s));
+            assert_eq!(triple.to_string(), s);
+        }
+        assert!("x86_64-unknown-linux-gnux".parse::<TargetTriple>().is_err());
+    }
+
+    #[test]
+    fn hash() {
+        let set: std::collections::HashSet<TargetTriple> = [
+            "x86_64-unknown-linux-gnu".parse().unwrap(),
+            TargetTriple::from_target_triple("x86_64-unknown-linux-gnu"),
+            "x86_64-unknown-linux-musl".parse().unwrap(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let triple: TargetTriple = "x86_64-unknown-linux-gnu".parse().unwrap();
+        let json = serde_json::to_string(&triple).unwrap();
+        assert_eq!(json, "\"x86_64-unknown-linux-gnu\"");
+        assert_eq!(serde_json::from_str::<TargetTriple>(&json).unwrap(), triple);
+
+        let err =
+            serde_json::from_str::<TargetTriple>("\"x86_64-unknown-linux-gnux\"").unwrap_err();
+        assert!(err.to_string().contains("not a known target triple"));
+        assert!(serde_json::from_str::<TargetTriple>("42").is_err());
+    }
+
+    #[test]
+    fn fields() {
+        let cases = [
+            (
+                "x86_64-unknown-linux-gnu",
+                triple("x86_64", Some("unknown"), "linux", Some("gnu")),
+            ),
+            (
+                "aarch64-linux-android",
+                triple("aarch64", None, "linux", Some("android")),
+            ),
+            (
+                "thumbv7em-none-eabihf",
+                triple("thumbv7em", None, "none", Some("eabihf")),
+            ),
+            (
+                "x86_64-fortanix-unknown-sgx",
+                triple("x86_64", Some("fortanix"), "unknown", Some("sgx")),
+            ),
+        ];
+        for (s, expected) in cases {
+            assert_eq!(TargetTriple::from_target_triple(s), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_triple_display() {
+        let invalid = |suggestions: &[&str]| InvalidTriple {
+            triple: "x86_64-unknown-linux-gnux".to_string(),
+            suggestions: suggestions.iter().map(|s| s.to_string()).collect(),
+        };
+        assert_eq!(
+            invalid(&[]).to_string(),
+            "`x86_64-unknown-linux-gnux` is not a known target triple"
+        );
+        assert_eq!(
+            invalid(&["a"]).to_string(),
+            "`x86_64-unknown-linux-gnux` is not a known target triple, did you mean `a`?"
+        );
+        assert_eq!(
+            invalid(&["a", "b", "c"]).to_string(),
+            "`x86_64-unknown-linux-gnux` is not a known target triple, did you mean `a`, `b` or `c`?"
+        );
+    }
+
+    #[test]
+    fn closest_matches() {
+        let candidates = ["linux-x64", "linux-x86", "linux-arm64", "win64"];
+        assert_eq!(
+            closest("linux-x46", candidates.into_iter()),
+            ["linux-x86", "linux-x64"]
+        );
+        assert!(closest("solaris", candidates.into_iter()).is_empty());
+
+        let err = "x86_64-unknown-linux-gnux"
+            .parse::<TargetTriple>()
+            .unwrap_err();
+        assert_eq!(
+            err.suggestions.first().map(String::as_str),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert!(err.suggestions.len() <= MAX_SUGGESTIONS);
+        assert!("s390x-apple-ios-msvc".parse::<TargetTriple>().is_err());
+    }
+
+    #[test]
+    fn aliases_name_known_targets() {
+        for (alias, triple) in ALIASES {
+            assert!(targets::find(triple).is_some(), "{} -> {}", alias, triple);
+            assert_eq!(
+                TargetTriple::from_alias(alias).unwrap().to_string(),
+                *triple
+            );
+        }
+    }
+
+    #[test]
+    fn parse_aliases() {
+        let aliases: BTreeMap<String, String> = [
+            ("rpi4", "armv7-unknown-linux-gnueabihf"),
+            ("my-board", "thumbv7em-none-eabihf"),
+            ("broken", "x86_64-unknown-linux-gnux"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let parse = |name: &str| TargetTriple::parse_with_aliases(name, &aliases);
+
+        // User aliases win over built-in ones.
+        assert_eq!(
+            parse("rpi4").unwrap().to_string(),
+            "armv7-unknown-linux-gnueabihf"
+        );
+        assert_eq!(
+            parse("my-board").unwrap().to_string(),
+            "thumbv7em-none-eabihf"
+        );
+        assert_eq!(
+            parse("linux-x64").unwrap().to_string(),
+            "x86_64-unknown-linux-gnu"
+        );
+        assert_eq!(parse("wasm32-wasip1").unwrap().to_string(), "wasm32-wasip1");
+
+        let err = parse("broken").unwrap_err();
+        assert_eq!(err.triple, "x86_64-unknown-linux-gnux");
+
+        let err = parse("linux-x46").unwrap_err();
+        assert_eq!(err.triple, "linux-x46");
+        assert!(err.suggestions.contains(&"linux-x64".to_string()));
+        let err = parse("my-bord").unwrap_err();
+        assert_eq!(
+            err.suggestions.first().map(String::as_str),
+            Some("my-board")
+        );
+
+        let resolve = |name: &str| TargetTriple::resolve_alias(name, &aliases).to_string();
+        assert_eq!(resolve("rpi4"), "armv7-unknown-linux-gnueabihf");
+        assert_eq!(resolve("linux-x64"), "x86_64-unknown-linux-gnu");
+        assert_eq!(resolve("wasm32-wasi"), "wasm32-wasi");
+    }
+}